@@ -0,0 +1,242 @@
+use vulkano::device::{Device, Queue};
+use vulkano::buffer::{BufferUsage, DeviceLocalBuffer};
+use vulkano::framebuffer::{Subpass, RenderPass};
+use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
+use vulkano::descriptor::{DescriptorSet, PipelineLayoutAbstract};
+use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
+use vulkano::descriptor::pipeline_layout::PipelineLayout;
+use vulkano::pipeline::{ComputePipeline, GraphicsPipeline};
+use vulkano::pipeline::vertex::SingleBufferDefinition;
+use vulkano::pipeline::viewport::{Viewport, Scissor};
+use vulkano::sync::GpuFuture;
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use util::app::{Scene, Frame, MAX_FRAMES_IN_FLIGHT};
+use util::device::Queues;
+use util::render_pass::CustomRenderPassDesc;
+use vertex::staging_buffer::upload_buffer;
+
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+const PARTICLE_COUNT: usize = 8192;
+
+/// Must match `local_size_x` of the compute shader
+const WORKGROUP_SIZE: usize = 256;
+
+/// A particle as stored in the storage buffers, the layout must match the struct of the compute shader.
+/// Only position and color are read by the vertex shader.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Particle {
+    pub position: [f32; 2],
+    pub velocity: [f32; 2],
+    pub color: [f32; 4],
+}
+
+impl_vertex!(Particle, position, color);
+
+type ParticlePipeline = GraphicsPipeline<SingleBufferDefinition<Particle>, Box<PipelineLayoutAbstract + Send + Sync>, Arc<RenderPass<CustomRenderPassDesc>>>;
+
+type SimulationPipeline = ComputePipeline<PipelineLayout<cs::Layout>>;
+
+#[allow(unused)]
+pub mod cs {
+    #[derive(VulkanoShader)]
+    #[ty = "compute"]
+    #[path = "src/compute/shader.comp"]
+    struct Dummy;
+}
+
+#[allow(unused)]
+pub mod vs {
+    #[derive(VulkanoShader)]
+    #[ty = "vertex"]
+    #[path = "src/compute/shader.vert"]
+    struct Dummy;
+}
+
+#[allow(unused)]
+pub mod fs {
+    #[derive(VulkanoShader)]
+    #[ty = "fragment"]
+    #[path = "src/vertex/shader.frag"]
+    struct Dummy;
+}
+
+pub struct ComputeParticles;
+
+impl Tutorial for ComputeParticles {
+    fn name(&self) -> &'static str {
+        "compute_particles"
+    }
+
+    fn description(&self) -> &'static str {
+        "Compute shader particles, computed from their start state each frame instead of simulated step by step"
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        app::run("Compute Particles", config, ParticleScene::new)
+    }
+}
+
+/// Particles moving away from the center and bouncing off the borders of the window.
+///
+/// A frame may start before the previous one has finished, so the compute shader does not
+/// update the particles of the previous frame. It computes them from their start state and
+/// the time instead, and every frame in flight writes its own storage buffer.
+struct ParticleScene {
+    device: Arc<Device>,
+    compute_queue: Arc<Queue>,
+    simulation_pipeline: Arc<SimulationPipeline>,
+    /// The storage buffer of each frame in flight, and the descriptor set writing into it
+    particle_buffers: Vec<(Arc<DeviceLocalBuffer<[Particle]>>, Arc<DescriptorSet + Send + Sync>)>,
+    pipeline: Option<Arc<ParticlePipeline>>,
+}
+
+impl ParticleScene {
+    fn new(device: &Arc<Device>, queues: &Queues) -> ParticleScene {
+        if queues.compute.family().id() != queues.graphics.family().id() {
+            println!("Simulating on the dedicated compute queue family {}", queues.compute.family().id());
+        }
+        else {
+            println!("Simulating on the graphics queue family {}", queues.graphics.family().id());
+        }
+
+        let cs = cs::Shader::load(device.clone()).expect("failed to create shader module");
+        let simulation_pipeline = Arc::new(ComputePipeline::new(device.clone(), &cs.main_entry_point(), &()).unwrap());
+
+        let start_buffer = upload_buffer(device, &queues.transfer, create_particles().into_iter(), BufferUsage {
+            storage_buffer: true,
+            .. BufferUsage::none()
+        });
+
+        let particle_buffers = (0..MAX_FRAMES_IN_FLIGHT)
+            .map(|_| {
+                let buffer = create_particle_buffer(device, queues);
+                let descriptor_set = Arc::new(PersistentDescriptorSet::start(simulation_pipeline.clone(), 0)
+                    .add_buffer(start_buffer.clone()).unwrap()
+                    .add_buffer(buffer.clone()).unwrap()
+                    .build().unwrap()) as Arc<DescriptorSet + Send + Sync>;
+                (buffer, descriptor_set)
+            })
+            .collect();
+
+        ParticleScene {
+            device: device.clone(),
+            compute_queue: queues.compute.clone(),
+            simulation_pipeline: simulation_pipeline,
+            particle_buffers: particle_buffers,
+            pipeline: None,
+        }
+    }
+}
+
+impl Scene for ParticleScene {
+    fn create_pipeline(&mut self, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, dimensions: [u32; 2]) {
+        self.pipeline = Some(create_graphics_pipeline(&self.device, dimensions, render_pass));
+    }
+
+    fn draw(&mut self, builder: AutoCommandBufferBuilder, frame: &Frame) -> AutoCommandBufferBuilder {
+        let particles = self.particle_buffers[frame.index].0.clone();
+        builder.draw(self.pipeline.clone().unwrap(), DynamicState::none(), particles, (), ()).unwrap()
+    }
+
+    /// Run the simulation on the compute queue, the frame waits on a semaphore before the
+    /// vertex shader reads the particles
+    fn prepare_frame(&mut self, future: Box<GpuFuture>, frame: &Frame) -> Box<GpuFuture> {
+        let push_constants = cs::ty::PushConstants {
+            time: frame.time as f32,
+        };
+        let workgroups = ((PARTICLE_COUNT + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE) as u32;
+
+        let command_buffer = AutoCommandBufferBuilder::primary_one_time_submit(self.device.clone(), self.compute_queue.family()).unwrap()
+            .dispatch([workgroups, 1, 1], self.simulation_pipeline.clone(), self.particle_buffers[frame.index].1.clone(), push_constants).unwrap()
+            .build().unwrap();
+
+        Box::new(future
+            .then_execute(self.compute_queue.clone(), command_buffer).unwrap()
+            .then_signal_semaphore())
+    }
+}
+
+/// Buffer written by the compute shader and read as vertex buffer, shared by the graphics and compute families
+fn create_particle_buffer(device: &Arc<Device>, queues: &Queues) -> Arc<DeviceLocalBuffer<[Particle]>> {
+    let usage = BufferUsage {
+        storage_buffer: true,
+        vertex_buffer: true,
+        .. BufferUsage::none()
+    };
+    let mut families = vec![queues.graphics.family()];
+    if queues.compute.family().id() != queues.graphics.family().id() {
+        families.push(queues.compute.family());
+    }
+    DeviceLocalBuffer::array(device.clone(), PARTICLE_COUNT, usage, families).unwrap()
+}
+
+/// Particles on a disc around the center, moving outwards with random colors.
+/// The random numbers are always the same, so every run looks alike.
+fn create_particles() -> Vec<Particle> {
+    let mut random = Random::new(0x5eed);
+    (0..PARTICLE_COUNT)
+        .map(|_| {
+            let radius = 0.25 * random.next_f32().sqrt();
+            let angle = random.next_f32() * 2.0 * PI;
+            let (sin, cos) = angle.sin_cos();
+            Particle {
+                position: [radius * cos, radius * sin],
+                velocity: [0.25 * cos, 0.25 * sin],
+                color: [random.next_f32(), random.next_f32(), random.next_f32(), 1.0],
+            }
+        })
+        .collect()
+}
+
+/// Xorshift generator, good enough to scatter particles
+struct Random {
+    state: u32,
+}
+
+impl Random {
+    fn new(seed: u32) -> Random {
+        Random {
+            state: seed,
+        }
+    }
+
+    /// A number between 0 and 1
+    fn next_f32(&mut self) -> f32 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 17;
+        self.state ^= self.state << 5;
+        (self.state >> 8) as f32 / (1 << 24) as f32
+    }
+}
+
+fn create_graphics_pipeline(device: &Arc<Device>, dimensions: [u32; 2],
+        render_pass: &Arc<RenderPass<CustomRenderPassDesc>>) -> Arc<ParticlePipeline> {
+    let vs = vs::Shader::load(device.clone()).expect("failed to create shader module");
+    let fs = fs::Shader::load(device.clone()).expect("failed to create shader module");
+
+    let viewport = Viewport {
+        origin: [0.0, 0.0],
+        dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+        depth_range: 0.0 .. 1.0,
+    };
+
+    let scissor = Scissor {
+        origin: [0,0],
+        dimensions: dimensions,
+    };
+
+    Arc::new(GraphicsPipeline::start()
+        .vertex_input_single_buffer::<Particle>()
+        .vertex_shader(vs.main_entry_point(), ())
+        .point_list()
+        .viewports_scissors(Some((viewport, scissor)))
+        .fragment_shader(fs.main_entry_point(), ())
+        .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+        .build(device.clone())
+        .unwrap())
+}
@@ -0,0 +1 @@
+pub mod compute_particles;
@@ -0,0 +1,182 @@
+use vulkano::instance::Features;
+use vulkano::device::Device;
+use vulkano::buffer::{BufferUsage, CpuBufferPool, ImmutableBuffer};
+use vulkano::format::Format;
+use vulkano::image::ImmutableImage;
+use vulkano::sampler::Sampler;
+use vulkano::framebuffer::{Subpass, RenderPass};
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::descriptor::PipelineLayoutAbstract;
+use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
+use vulkano::pipeline::GraphicsPipeline;
+use vulkano::pipeline::vertex::SingleBufferDefinition;
+use vulkano::pipeline::viewport::{Viewport, Scissor};
+
+use clap::Arg;
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use util::app::{Scene, Frame, MAX_FRAMES_IN_FLIGHT};
+use util::device::Queues;
+use util::render_pass::CustomRenderPassDesc;
+use vertex::staging_buffer::upload_buffer;
+use vertex::index_buffer::Indices;
+use uniform::uniform_buffers::{UniformBufferObject, update_uniform_buffer};
+use texture::texture_mapping::{texture_arg, texture_path, load_image, create_texture_image, create_texture_sampler};
+
+pub use model::vertex::TexturedVertex3d;
+
+use std::sync::Arc;
+
+
+/// Two rectangles, the second one below the first
+const VERTICES: [TexturedVertex3d; 8] = [
+    TexturedVertex3d { position: [-0.5, -0.5, 0.0], color: [1.0, 0.0, 0.0], tex_coord: [1.0, 0.0] },
+    TexturedVertex3d { position: [0.5, -0.5, 0.0], color: [0.0, 1.0, 0.0], tex_coord: [0.0, 0.0] },
+    TexturedVertex3d { position: [0.5, 0.5, 0.0], color: [0.0, 0.0, 1.0], tex_coord: [0.0, 1.0] },
+    TexturedVertex3d { position: [-0.5, 0.5, 0.0], color: [1.0, 1.0, 1.0], tex_coord: [1.0, 1.0] },
+
+    TexturedVertex3d { position: [-0.5, -0.5, -0.5], color: [1.0, 0.0, 0.0], tex_coord: [1.0, 0.0] },
+    TexturedVertex3d { position: [0.5, -0.5, -0.5], color: [0.0, 1.0, 0.0], tex_coord: [0.0, 0.0] },
+    TexturedVertex3d { position: [0.5, 0.5, -0.5], color: [0.0, 0.0, 1.0], tex_coord: [0.0, 1.0] },
+    TexturedVertex3d { position: [-0.5, 0.5, -0.5], color: [1.0, 1.0, 1.0], tex_coord: [1.0, 1.0] },
+];
+
+const INDICES: [u32; 12] = [
+    0, 1, 2, 2, 3, 0,
+    4, 5, 6, 6, 7, 4,
+];
+
+pub type MeshPipeline = GraphicsPipeline<SingleBufferDefinition<TexturedVertex3d>, Box<PipelineLayoutAbstract + Send + Sync>, Arc<RenderPass<CustomRenderPassDesc>>>;
+
+#[allow(unused)]
+pub mod vs {
+    #[derive(VulkanoShader)]
+    #[ty = "vertex"]
+    #[path = "src/depth/shader.vert"]
+    struct Dummy;
+}
+
+#[allow(unused)]
+pub mod fs {
+    #[derive(VulkanoShader)]
+    #[ty = "fragment"]
+    #[path = "src/texture/shader.frag"]
+    struct Dummy;
+}
+
+pub struct DepthBuffering;
+
+impl Tutorial for DepthBuffering {
+    fn name(&self) -> &'static str {
+        "depth_buffering"
+    }
+
+    fn description(&self) -> &'static str {
+        "Depth buffering"
+    }
+
+    fn args(&self) -> Vec<Arg<'static, 'static>> {
+        vec![
+            texture_arg(),
+        ]
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        let image = load_image(&texture_path(config))?;
+
+        let features = Features {
+            sampler_anisotropy: true,
+            .. Features::none()
+        };
+        app::run_with_features("Depth Buffering", config, &features, move |device, queues| {
+            let texture = create_texture_image(&queues.graphics, &image);
+            MeshScene::new(device, queues, &VERTICES, &INDICES, texture, create_texture_sampler(device))
+        })
+    }
+}
+
+/// Draws a rotating textured mesh with depth testing, later chapters only change the mesh and the texture
+pub struct MeshScene {
+    device: Arc<Device>,
+    vertex_buffer: Arc<ImmutableBuffer<[TexturedVertex3d]>>,
+    indices: Indices,
+    uniform_buffers: Vec<CpuBufferPool<UniformBufferObject>>,
+    texture: Arc<ImmutableImage<Format>>,
+    sampler: Arc<Sampler>,
+    pipeline: Option<Arc<MeshPipeline>>,
+    aspect_ratio: f32,
+}
+
+impl MeshScene {
+    pub fn new(device: &Arc<Device>, queues: &Queues, vertices: &[TexturedVertex3d], indices: &[u32],
+            texture: Arc<ImmutableImage<Format>>, sampler: Arc<Sampler>) -> MeshScene {
+        let uniform_buffers = (0..MAX_FRAMES_IN_FLIGHT)
+            .map(|_| CpuBufferPool::new(device.clone(), BufferUsage::uniform_buffer()))
+            .collect();
+
+        MeshScene {
+            device: device.clone(),
+            vertex_buffer: upload_buffer(device, &queues.transfer, vertices.iter().cloned(), BufferUsage::vertex_buffer()),
+            indices: Indices::new(device, &queues.transfer, indices),
+            uniform_buffers: uniform_buffers,
+            texture: texture,
+            sampler: sampler,
+            pipeline: None,
+            aspect_ratio: 1.0,
+        }
+    }
+}
+
+impl Scene for MeshScene {
+    fn create_pipeline(&mut self, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, dimensions: [u32; 2]) {
+        self.pipeline = Some(create_graphics_pipeline(&self.device, dimensions, render_pass));
+        self.aspect_ratio = dimensions[0] as f32 / dimensions[1] as f32;
+    }
+
+    fn draw(&mut self, builder: AutoCommandBufferBuilder, frame: &Frame) -> AutoCommandBufferBuilder {
+        let pipeline = self.pipeline.clone().unwrap();
+
+        let uniform_buffer = self.uniform_buffers[frame.index]
+            .next(update_uniform_buffer(frame.time, self.aspect_ratio)).unwrap();
+        let descriptor_set = Arc::new(PersistentDescriptorSet::start(pipeline.clone(), 0)
+            .add_buffer(uniform_buffer).unwrap()
+            .add_sampled_image(self.texture.clone(), self.sampler.clone()).unwrap()
+            .build().unwrap());
+
+        self.indices.draw(builder, pipeline, self.vertex_buffer.clone(), descriptor_set)
+    }
+
+    fn depth_buffer(&self) -> bool {
+        true
+    }
+}
+
+/// Pipeline for textured meshes, fragments behind already drawn ones are discarded
+pub fn create_graphics_pipeline(device: &Arc<Device>, dimensions: [u32; 2],
+        render_pass: &Arc<RenderPass<CustomRenderPassDesc>>) -> Arc<MeshPipeline> {
+    let vs = vs::Shader::load(device.clone()).expect("failed to create shader module");
+    let fs = fs::Shader::load(device.clone()).expect("failed to create shader module");
+
+    let viewport = Viewport {
+        origin: [0.0, 0.0],
+        dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+        depth_range: 0.0 .. 1.0,
+    };
+
+    let scissor = Scissor {
+        origin: [0,0],
+        dimensions: dimensions,
+    };
+
+    Arc::new(GraphicsPipeline::start()
+        .vertex_input_single_buffer::<TexturedVertex3d>()
+        .vertex_shader(vs.main_entry_point(), ())
+        .triangle_list()
+        .viewports_scissors(Some((viewport, scissor)))
+        .fragment_shader(fs.main_entry_point(), ())
+        .depth_stencil_simple_depth()
+        .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+        .build(device.clone())
+        .unwrap())
+}
@@ -0,0 +1 @@
+pub mod depth_buffering;
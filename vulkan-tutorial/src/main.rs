@@ -6,74 +6,135 @@ use clap::*;
 #[macro_use]
 extern crate vulkano_shader_derive;
 
+#[macro_use]
 extern crate vulkano;
 
 extern crate vk_sys;
 extern crate vulkano_glfw;
+extern crate image;
+extern crate cgmath;
 
 mod triangle;
+mod vertex;
+mod uniform;
+mod texture;
+mod depth;
+mod model;
+mod mipmap;
+mod msaa;
+mod compute;
+mod tutorial;
 mod util;
 
-use triangle::hello_triangle;
+use triangle::hello_triangle::HelloTriangle;
+use vertex::vertex_buffers::VertexBuffers;
+use vertex::staging_buffer::StagingBuffer;
+use vertex::index_buffer::IndexBuffer;
+use uniform::uniform_buffers::UniformBuffers;
+use texture::texture_mapping::TextureMapping;
+use depth::depth_buffering::DepthBuffering;
+use model::loading_models::LoadingModels;
+use mipmap::generating_mipmaps::GeneratingMipmaps;
+use msaa::multisampling::Multisampling;
+use compute::compute_particles::ComputeParticles;
+use tutorial::{Tutorial, Config, Registry, Error};
+
+use std::process;
+
+/// All chapters of the tutorial, a new chapter only has to be registered here
+fn tutorials() -> Registry {
+    let mut registry = Registry::new();
+    registry.register(HelloTriangle);
+    registry.register(VertexBuffers);
+    registry.register(StagingBuffer);
+    registry.register(IndexBuffer);
+    registry.register(UniformBuffers);
+    registry.register(TextureMapping);
+    registry.register(DepthBuffering);
+    registry.register(LoadingModels);
+    registry.register(GeneratingMipmaps);
+    registry.register(Multisampling);
+    registry.register(ComputeParticles);
+    registry
+}
 
-struct Command<'a> {
-    name: &'a str,
-    description: &'a str,
-    main_function: fn(),
+fn utils() -> Registry {
+    let mut registry = Registry::new();
+    registry.register(util::info::ShowDevices);
+    registry.register(ShowTutorials);
+    registry
 }
 
-const TUTORIALS: &[Command; 1] = &[
-    Command {
-        name: "hello_triangle",
-        description: "Hello triangle",
-        main_function: hello_triangle::app_main,
-    },
-];
-
-const UTILS: &[Command; 1] = &[
-    Command {
-        name: "devices",
-        description: "List physical devices",
-        main_function: util::info::show_physical_devices,
-    },
-];
-
-
-fn find_command<'a>(commands: &'a [Command], name: &str) -> Option<&'a Command<'a>> {
-    for t in commands {
-        if t.name == name {
-            return Some(t);
+/// Prints one tutorial per line, starting with its name
+struct ShowTutorials;
+
+impl Tutorial for ShowTutorials {
+    fn name(&self) -> &'static str {
+        "tutorials"
+    }
+
+    fn description(&self) -> &'static str {
+        "List available tutorials"
+    }
+
+    fn run(&self, _config: &Config) -> Result<(), Error> {
+        for t in tutorials().iter() {
+            println!("{:<24}{}", t.name(), t.description());
         }
+        Ok(())
     }
-    None
 }
 
-fn add_sub_command<'a>(app: App<'static, 'static>, name: &'a str, about: &'static str, commands: &'static [Command]) -> App<'static, 'static> {
+fn tutorial_args() -> Vec<Arg<'static, 'static>> {
+    vec![
+        Arg::with_name("headless")
+            .long("headless")
+            .help("Render into an offscreen image instead of a window"),
+        Arg::with_name("frames")
+            .long("frames")
+            .takes_value(true)
+            .value_name("N")
+            .help("Number of frames to render before exiting"),
+        Arg::with_name("screenshot")
+            .long("screenshot")
+            .takes_value(true)
+            .value_name("PATH")
+            .help("Save the last rendered frame as PNG file"),
+    ]
+}
+
+fn add_sub_command<'a>(app: App<'static, 'static>, name: &'a str, about: &'static str, registry: &Registry,
+        args: &[Arg<'static, 'static>]) -> App<'static, 'static> {
     let mut sub_command = SubCommand::with_name(name).about(about);
-    for t in commands {
-        sub_command = sub_command.subcommand(SubCommand::with_name(t.name).about(t.description));
+    for t in registry.iter() {
+        sub_command = sub_command.subcommand(SubCommand::with_name(t.name()).about(t.description())
+            .args(args)
+            .args(&t.args()));
     }
     app.subcommand(sub_command)
 }
 
-fn execute_command(name: &str, matches: &ArgMatches) {
-    let command_matches = matches.subcommand_matches(name).unwrap();
-    let sub_name = command_matches.subcommand_name().unwrap();
-    let command = if name=="run" {
-        find_command(TUTORIALS , sub_name)
+fn execute_command(name: &str, matches: &ArgMatches) -> Result<(), Error> {
+    let registry = if name=="run" {
+        tutorials()
     }
     else if name=="show" {
-        find_command(UTILS , sub_name)
+        utils()
     }
     else {
-        panic!("Unknown subcommand")
+        return Err(Error::UnknownCommand(name.to_string()))
     };
 
-    match command {
-        Some(t) => (t.main_function)(),
-        None => panic!("Unknown executable")
+    let command_matches = matches.subcommand_matches(name)
+        .ok_or_else(|| Error::UnknownCommand(name.to_string()))?;
+    let sub_name = command_matches.subcommand_name()
+        .ok_or_else(|| Error::UnknownCommand(format!("{} needs a sub command, see --help", name)))?;
+    let args = command_matches.subcommand_matches(sub_name).unwrap();
+    let config = Config::from_matches(args)?;
+    match registry.find(sub_name) {
+        Some(t) => t.run(&config),
+        None => Err(Error::UnknownCommand(format!("{} {}", name, sub_name)))
     }
-
 }
 
 fn main() {
@@ -82,16 +143,17 @@ fn main() {
                     .author("André Twupack <atwupack@mailbox.org>")
                     .about("Vulkan Tutorials from vulkan-tutorial.com");
 
-    app = add_sub_command(app, "run", "Run a tutorial", TUTORIALS);
-    app = add_sub_command(app, "show", "Show system info", UTILS);
+    app = add_sub_command(app, "run", "Run a tutorial", &tutorials(), &tutorial_args());
+    app = add_sub_command(app, "show", "Show system info", &utils(), &[]);
 
     let matches = app.get_matches();
 
-    let sub = matches.subcommand_name();
-    match sub {
-        Some(name) => {
-            execute_command(name, &matches);
-        },
-        None => panic!("Unknown subcommand")
+    let result = match matches.subcommand_name() {
+        Some(name) => execute_command(name, &matches),
+        None => Err(Error::UnknownCommand("no command given, see --help".to_string())),
+    };
+    if let Err(err) = result {
+        eprintln!("{}", err);
+        process::exit(1);
     }
 }
@@ -0,0 +1,222 @@
+use vk_sys as vk;
+
+use vulkano::instance::Features;
+use vulkano::device::{Device, Queue};
+use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
+use vulkano::format::Format;
+use vulkano::image::{Dimensions, ImageUsage, ImageLayout, ImmutableImage, StorageImage, MipmapsCount};
+use vulkano::image::immutable::ImmutableImageInitialization;
+use vulkano::sampler::{Sampler, Filter, MipmapMode, SamplerAddressMode, SamplerCreationError};
+use vulkano::command_buffer::{AutoCommandBufferBuilder, CommandBuffer};
+use vulkano::sync::GpuFuture;
+
+use clap::Arg;
+
+use image::RgbaImage;
+use image::imageops;
+use image::imageops::FilterType;
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use util::format::supports_optimal_tiling_features;
+use depth::depth_buffering::MeshScene;
+use model::loading_models::{model_arg, load_textured_model};
+use texture::texture_mapping::{texture_arg, max_anisotropy};
+
+use std::cmp::max;
+use std::sync::Arc;
+
+const TEXTURE_FORMAT: Format = Format::R8G8B8A8Srgb;
+
+pub struct GeneratingMipmaps;
+
+impl Tutorial for GeneratingMipmaps {
+    fn name(&self) -> &'static str {
+        "generating_mipmaps"
+    }
+
+    fn description(&self) -> &'static str {
+        "Generating mipmaps"
+    }
+
+    fn args(&self) -> Vec<Arg<'static, 'static>> {
+        vec![
+            model_arg(),
+            texture_arg(),
+            Arg::with_name("lod-bias")
+                .long("lod-bias")
+                .takes_value(true)
+                .value_name("BIAS")
+                .allow_hyphen_values(true)
+                .help("Added to the mip level chosen by the sampler, positive values make the texture blurrier"),
+            Arg::with_name("max-lod")
+                .long("max-lod")
+                .takes_value(true)
+                .value_name("LOD")
+                .help("Highest mip level the sampler uses, defaults to the smallest level"),
+        ]
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        let (model, image) = load_textured_model(config)?;
+
+        let levels = mip_levels(image.width(), image.height());
+        let lod_bias = float_value(config, "lod-bias")?.unwrap_or(0.0);
+        let max_lod = float_value(config, "max-lod")?.unwrap_or(levels as f32);
+        if max_lod < 0.0 {
+            return Err(Error::InvalidArgument(format!("max-lod must not be negative, got {}", max_lod)));
+        }
+
+        let features = Features {
+            sampler_anisotropy: true,
+            .. Features::none()
+        };
+        app::try_run_with_features("Generating Mipmaps", config, &features, move |device, queues| {
+            // vulkano only checks the upper limit, but the bias is valid in the range -limit to limit
+            let max_lod_bias = device.physical_device().limits().max_sampler_lod_bias();
+            if lod_bias.abs() > max_lod_bias {
+                return Err(Error::InvalidArgument(format!("lod-bias must be between -{} and {} for this device, got {}",
+                                                          max_lod_bias, max_lod_bias, lod_bias)));
+            }
+            let texture = create_mipmapped_texture_image(&queues.graphics, &image);
+            let sampler = create_mipmap_sampler(device, lod_bias, max_lod)?;
+            Ok(MeshScene::new(device, queues, &model.vertices, &model.indices, texture, sampler))
+        })
+    }
+}
+
+fn float_value(config: &Config, name: &str) -> Result<Option<f32>, Error> {
+    match config.value_of(name) {
+        Some(value) => value.parse::<f32>()
+            .map(Some)
+            .map_err(|_| Error::InvalidArgument(format!("{} must be a number, got '{}'", name, value))),
+        None => Ok(None),
+    }
+}
+
+/// Number of levels in a full mip chain, the last level has a size of 1x1
+pub fn mip_levels(width: u32, height: u32) -> u32 {
+    32 - max(width, height).leading_zeros()
+}
+
+/// Size of a mip level along one axis
+fn mip_size(size: u32, level: u32) -> u32 {
+    max(1, size >> level)
+}
+
+/// Upload the image and fill all levels of its mip chain.
+///
+/// The levels are generated on the GPU by blitting each level into the next smaller one,
+/// which needs linear filtering for the format. Otherwise they are downsampled on the CPU.
+pub fn create_mipmapped_texture_image(queue: &Arc<Queue>, image: &RgbaImage) -> Arc<ImmutableImage<Format>> {
+    let device = queue.device();
+    let (width, height) = image.dimensions();
+    let levels = mip_levels(width, height);
+
+    let usage = ImageUsage {
+        transfer_destination: true,
+        sampled: true,
+        .. ImageUsage::none()
+    };
+    let (texture, init) = ImmutableImage::uninitialized(device.clone(),
+                                                        Dimensions::Dim2d { width: width, height: height },
+                                                        TEXTURE_FORMAT,
+                                                        MipmapsCount::Specific(levels),
+                                                        usage,
+                                                        ImageLayout::ShaderReadOnlyOptimal,
+                                                        Some(queue.family())).unwrap();
+    // the access for the upload can not be cloned, but every level is written by its own command
+    let init = Arc::new(init);
+
+    let blit_features = vk::FORMAT_FEATURE_SAMPLED_IMAGE_FILTER_LINEAR_BIT | vk::FORMAT_FEATURE_BLIT_SRC_BIT | vk::FORMAT_FEATURE_BLIT_DST_BIT;
+    let builder = AutoCommandBufferBuilder::new(device.clone(), queue.family()).unwrap();
+    let builder = if supports_optimal_tiling_features(device.physical_device(), TEXTURE_FORMAT, blit_features) {
+        println!("Generating {} mip levels by blitting on the GPU", levels);
+        blit_mipmaps(builder, device, queue, image, levels, init)
+    }
+    else {
+        println!("Generating {} mip levels on the CPU, the format does not support linear blitting", levels);
+        downsample_mipmaps(builder, device, image, levels, init)
+    };
+
+    builder.build().unwrap()
+        .execute(queue.clone()).unwrap()
+        .then_signal_fence_and_flush().unwrap()
+        .wait(None).unwrap();
+
+    texture
+}
+
+/// Generate the levels with a chain of blits, each level is half the size of the previous one.
+///
+/// vulkano tracks the layout of an image as a whole, not per mip level, so a blit between two
+/// levels of the same image is rejected. The chain is built in one image per level instead
+/// and every level is then copied into the texture.
+fn blit_mipmaps(builder: AutoCommandBufferBuilder, device: &Arc<Device>, queue: &Arc<Queue>, image: &RgbaImage,
+        levels: u32, texture: Arc<ImmutableImageInitialization<Format>>) -> AutoCommandBufferBuilder {
+    let (width, height) = image.dimensions();
+    let usage = ImageUsage {
+        transfer_source: true,
+        transfer_destination: true,
+        sampled: true,
+        .. ImageUsage::none()
+    };
+    let level_images: Vec<_> = (0..levels)
+        .map(|level| {
+            let dimensions = Dimensions::Dim2d { width: mip_size(width, level), height: mip_size(height, level) };
+            StorageImage::with_usage(device.clone(), dimensions, TEXTURE_FORMAT, usage, Some(queue.family())).unwrap()
+        })
+        .collect();
+
+    let source = CpuAccessibleBuffer::from_iter(device.clone(), BufferUsage::transfer_source(), image.iter().cloned()).unwrap();
+    let mut builder = builder.copy_buffer_to_image(source, level_images[0].clone()).unwrap();
+
+    for level in 1..levels {
+        let source_size = [mip_size(width, level - 1) as i32, mip_size(height, level - 1) as i32, 1];
+        let destination_size = [mip_size(width, level) as i32, mip_size(height, level) as i32, 1];
+        builder = builder.blit_image(level_images[level as usize - 1].clone(), [0, 0, 0], source_size, 0, 0,
+                                     level_images[level as usize].clone(), [0, 0, 0], destination_size, 0, 0,
+                                     1, Filter::Linear).unwrap();
+    }
+
+    for level in 0..levels {
+        let extent = [mip_size(width, level), mip_size(height, level), 1];
+        builder = builder.copy_image(level_images[level as usize].clone(), [0, 0, 0], 0, 0,
+                                     texture.clone(), [0, 0, 0], 0, level,
+                                     extent, 1).unwrap();
+    }
+    builder
+}
+
+/// Generate the levels on the CPU and upload each of them
+fn downsample_mipmaps(mut builder: AutoCommandBufferBuilder, device: &Arc<Device>, image: &RgbaImage,
+        levels: u32, texture: Arc<ImmutableImageInitialization<Format>>) -> AutoCommandBufferBuilder {
+    let mut level_image = image.clone();
+
+    for level in 0..levels {
+        if level > 0 {
+            let (width, height) = level_image.dimensions();
+            level_image = imageops::resize(&level_image, max(1, width / 2), max(1, height / 2), FilterType::Triangle);
+        }
+        let (width, height) = level_image.dimensions();
+        let source = CpuAccessibleBuffer::from_iter(device.clone(), BufferUsage::transfer_source(), level_image.iter().cloned()).unwrap();
+        builder = builder.copy_buffer_to_image_dimensions(source, texture.clone(), [0, 0, 0], [width, height, 1], 0, 1, level).unwrap();
+    }
+    builder
+}
+
+/// Sampler using all levels of the mip chain.
+/// `lod_bias` is added to the computed level of detail, which is clamped to `max_lod`.
+pub fn create_mipmap_sampler(device: &Arc<Device>, lod_bias: f32, max_lod: f32) -> Result<Arc<Sampler>, SamplerCreationError> {
+    Sampler::new(device.clone(),
+                 Filter::Linear,
+                 Filter::Linear,
+                 MipmapMode::Linear,
+                 SamplerAddressMode::Repeat,
+                 SamplerAddressMode::Repeat,
+                 SamplerAddressMode::Repeat,
+                 lod_bias,
+                 max_anisotropy(device),
+                 0.0, // min_lod
+                 max_lod)
+}
@@ -0,0 +1 @@
+pub mod generating_mipmaps;
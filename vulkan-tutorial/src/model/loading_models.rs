@@ -0,0 +1,69 @@
+use vulkano::instance::Features;
+
+use clap::Arg;
+
+use image::RgbaImage;
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use depth::depth_buffering::MeshScene;
+use model::obj::{Model, load_model};
+use texture::texture_mapping::{texture_arg, texture_path, load_image, create_texture_image, create_texture_sampler};
+
+use std::path::Path;
+
+/// Used if no model is given on the command line
+const DEFAULT_MODEL: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/models/cube.obj");
+
+pub struct LoadingModels;
+
+impl Tutorial for LoadingModels {
+    fn name(&self) -> &'static str {
+        "loading_models"
+    }
+
+    fn description(&self) -> &'static str {
+        "Loading models"
+    }
+
+    fn args(&self) -> Vec<Arg<'static, 'static>> {
+        vec![
+            model_arg(),
+            texture_arg(),
+        ]
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        let (model, image) = load_textured_model(config)?;
+
+        let features = Features {
+            sampler_anisotropy: true,
+            .. Features::none()
+        };
+        app::run_with_features("Loading Models", config, &features, move |device, queues| {
+            let texture = create_texture_image(&queues.graphics, &image);
+            MeshScene::new(device, queues, &model.vertices, &model.indices, texture, create_texture_sampler(device))
+        })
+    }
+}
+
+/// The argument for the OBJ file of the model
+pub fn model_arg() -> Arg<'static, 'static> {
+    Arg::with_name("model")
+        .long("model")
+        .takes_value(true)
+        .value_name("PATH")
+        .help("Wavefront OBJ file of the model")
+}
+
+/// Load the model and its texture, a texture given on the command line replaces the one of the material
+pub fn load_textured_model(config: &Config) -> Result<(Model, RgbaImage), Error> {
+    let model = load_model(Path::new(config.value_of("model").unwrap_or(DEFAULT_MODEL)))?;
+    println!("Loaded model with {} vertices and {} indices", model.vertices.len(), model.indices.len());
+
+    let image = match (config.value_of("texture"), model.texture.as_ref()) {
+        (None, Some(texture)) => load_image(texture)?,
+        _ => load_image(&texture_path(config))?,
+    };
+    Ok((model, image))
+}
@@ -0,0 +1,349 @@
+//! Parser for Wavefront OBJ files and their MTL material libraries.
+//!
+//! Only what is needed to draw a textured mesh is read: positions, texture coordinates,
+//! faces and the diffuse texture of the materials. Polygons are split into triangles
+//! and vertices sharing position and texture coordinate are only stored once.
+
+use model::vertex::TexturedVertex3d;
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::str::SplitWhitespace;
+
+/// Color of the vertices, the color of the model comes from its texture
+const VERTEX_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+
+/// A triangle mesh ready to be uploaded into vertex and index buffers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Model {
+    pub vertices: Vec<TexturedVertex3d>,
+    pub indices: Vec<u32>,
+    /// Diffuse texture of the first material with one, relative to the current directory
+    pub texture: Option<PathBuf>,
+}
+
+/// The content of an OBJ file
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjData {
+    pub vertices: Vec<TexturedVertex3d>,
+    pub indices: Vec<u32>,
+    /// Files given with `mtllib`, relative to the OBJ file
+    pub material_libraries: Vec<String>,
+    /// Materials selected with `usemtl`, in the order of their first use
+    pub materials: Vec<String>,
+}
+
+/// A material of an MTL file
+#[derive(Debug, Clone, PartialEq)]
+pub struct Material {
+    pub name: String,
+    /// Image given with `map_Kd`, relative to the MTL file
+    pub diffuse_texture: Option<String>,
+}
+
+/// Errors reported while loading a model
+#[derive(Debug)]
+pub enum ObjError {
+    Io(io::Error),
+    /// A line could not be parsed, lines are counted from 1
+    Parse { line: usize, message: String },
+}
+
+impl From<io::Error> for ObjError {
+    fn from(err: io::Error) -> ObjError {
+        ObjError::Io(err)
+    }
+}
+
+impl error::Error for ObjError {
+    fn description(&self) -> &str {
+        match *self {
+            ObjError::Io(_) => "I/O error",
+            ObjError::Parse { .. } => "Invalid OBJ or MTL file",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ObjError::Io(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ObjError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ObjError::Io(ref err) => write!(f, "I/O error: {}", err),
+            ObjError::Parse { line, ref message } => write!(f, "line {}: {}", line, message),
+        }
+    }
+}
+
+/// Load an OBJ file and the texture of its materials
+pub fn load_model(path: &Path) -> Result<Model, ObjError> {
+    let obj = parse_obj(BufReader::new(File::open(path)?))?;
+    let obj_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut materials = Vec::new();
+    for library in &obj.material_libraries {
+        let library_path = obj_dir.join(library);
+        let library_dir = library_path.parent().unwrap_or(obj_dir).to_path_buf();
+        for material in parse_mtl(BufReader::new(File::open(&library_path)?))? {
+            materials.push((library_dir.clone(), material));
+        }
+    }
+
+    // a model is drawn with a single texture, so the first material having one is taken
+    let texture = obj.materials.iter()
+        .filter_map(|name| materials.iter().find(|(_, material)| material.name == *name))
+        .filter_map(|(dir, material)| material.diffuse_texture.as_ref().map(|texture| dir.join(texture)))
+        .next();
+
+    Ok(Model {
+        vertices: obj.vertices,
+        indices: obj.indices,
+        texture: texture,
+    })
+}
+
+/// Parse the content of an OBJ file
+pub fn parse_obj<R: BufRead>(reader: R) -> Result<ObjData, ObjError> {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut tex_coords: Vec<[f32; 2]> = Vec::new();
+    let mut unique_vertices: HashMap<(usize, Option<usize>), u32> = HashMap::new();
+    let mut data = ObjData {
+        vertices: Vec::new(),
+        indices: Vec::new(),
+        material_libraries: Vec::new(),
+        materials: Vec::new(),
+    };
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = number + 1;
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("v") => {
+                let x = next_number(&mut tokens, line_number)?;
+                let y = next_number(&mut tokens, line_number)?;
+                let z = next_number(&mut tokens, line_number)?;
+                positions.push([x, y, z]);
+            },
+            Some("vt") => {
+                let u = next_number(&mut tokens, line_number)?;
+                let v = next_number(&mut tokens, line_number)?;
+                // OBJ puts the origin of the texture at the bottom, Vulkan at the top
+                tex_coords.push([u, 1.0 - v]);
+            },
+            Some("f") => {
+                let mut face = Vec::new();
+                for token in tokens {
+                    let (position, tex_coord) = parse_face_vertex(token, positions.len(), tex_coords.len(), line_number)?;
+                    let vertices = &mut data.vertices;
+                    let index = *unique_vertices.entry((position, tex_coord)).or_insert_with(|| {
+                        vertices.push(TexturedVertex3d {
+                            position: positions[position],
+                            color: VERTEX_COLOR,
+                            tex_coord: tex_coord.map_or([0.0, 0.0], |t| tex_coords[t]),
+                        });
+                        (vertices.len() - 1) as u32
+                    });
+                    face.push(index);
+                }
+                if face.len() < 3 {
+                    return Err(parse_error(line_number, "a face needs at least three vertices"));
+                }
+                // polygons are split into a fan of triangles
+                for i in 1..face.len() - 1 {
+                    data.indices.extend_from_slice(&[face[0], face[i], face[i + 1]]);
+                }
+            },
+            Some("mtllib") => {
+                data.material_libraries.extend(tokens.map(|name| name.to_owned()));
+            },
+            Some("usemtl") => {
+                let name = next_name(&mut tokens, line_number)?;
+                if !data.materials.contains(&name) {
+                    data.materials.push(name);
+                }
+            },
+            // normals, groups, smoothing and comments are not needed
+            _ => (),
+        }
+    }
+
+    Ok(data)
+}
+
+/// Parse the content of an MTL file
+pub fn parse_mtl<R: BufRead>(reader: R) -> Result<Vec<Material>, ObjError> {
+    let mut materials: Vec<Material> = Vec::new();
+
+    for (number, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line_number = number + 1;
+        let mut tokens = line.split_whitespace();
+
+        match tokens.next() {
+            Some("newmtl") => {
+                materials.push(Material {
+                    name: next_name(&mut tokens, line_number)?,
+                    diffuse_texture: None,
+                });
+            },
+            Some("map_Kd") => {
+                // options like `-bm 1.0` come before the file name, which is the last token
+                let texture = tokens.last()
+                    .ok_or_else(|| parse_error(line_number, "missing texture file"))?;
+                match materials.last_mut() {
+                    Some(material) => material.diffuse_texture = Some(texture.to_owned()),
+                    None => return Err(parse_error(line_number, "map_Kd before newmtl")),
+                }
+            },
+            _ => (),
+        }
+    }
+
+    Ok(materials)
+}
+
+fn parse_error(line: usize, message: &str) -> ObjError {
+    ObjError::Parse {
+        line: line,
+        message: message.to_owned(),
+    }
+}
+
+fn next_name(tokens: &mut SplitWhitespace, line: usize) -> Result<String, ObjError> {
+    tokens.next()
+        .map(|name| name.to_owned())
+        .ok_or_else(|| parse_error(line, "missing name"))
+}
+
+fn next_number(tokens: &mut SplitWhitespace, line: usize) -> Result<f32, ObjError> {
+    let token = tokens.next().ok_or_else(|| parse_error(line, "missing number"))?;
+    token.parse().map_err(|_| parse_error(line, &format!("invalid number '{}'", token)))
+}
+
+/// Parse a vertex of a face like `1`, `1/2`, `1/2/3` or `1//3` into the zero based
+/// indices of its position and texture coordinate
+fn parse_face_vertex(token: &str, positions: usize, tex_coords: usize, line: usize) -> Result<(usize, Option<usize>), ObjError> {
+    let mut parts = token.split('/');
+    let position = match parts.next() {
+        Some(index) => resolve_index(index, positions, line)?,
+        None => return Err(parse_error(line, "missing position index")),
+    };
+    let tex_coord = match parts.next() {
+        Some("") | None => None,
+        Some(index) => Some(resolve_index(index, tex_coords, line)?),
+    };
+    Ok((position, tex_coord))
+}
+
+/// Indices start at 1, negative indices count back from the last element read so far
+fn resolve_index(index: &str, count: usize, line: usize) -> Result<usize, ObjError> {
+    let value = index.parse::<i64>().map_err(|_| parse_error(line, &format!("invalid index '{}'", index)))?;
+    let resolved = if value < 0 { count as i64 + value } else { value - 1 };
+    if resolved < 0 || resolved >= count as i64 {
+        return Err(parse_error(line, &format!("index {} out of range", value)));
+    }
+    Ok(resolved as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::Cursor;
+
+    fn fixture(name: &str) -> PathBuf {
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("fixtures").join(name)
+    }
+
+    fn parse(content: &str) -> Result<ObjData, ObjError> {
+        parse_obj(Cursor::new(content))
+    }
+
+    #[test]
+    fn shared_vertices_are_deduplicated() {
+        let model = load_model(&fixture("quad.obj")).unwrap();
+        assert_eq!(model.vertices.len(), 4);
+        assert_eq!(model.indices, vec![0, 1, 2, 0, 2, 3]);
+        assert_eq!(model.vertices[2].position, [1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn texture_coordinates_are_flipped() {
+        let model = load_model(&fixture("quad.obj")).unwrap();
+        assert_eq!(model.vertices[0].tex_coord, [0.0, 1.0]);
+        assert_eq!(model.vertices[2].tex_coord, [1.0, 0.0]);
+    }
+
+    #[test]
+    fn texture_is_relative_to_material_library() {
+        let model = load_model(&fixture("quad.obj")).unwrap();
+        assert_eq!(model.texture, Some(fixture("quad.png")));
+    }
+
+    #[test]
+    fn vertices_with_different_tex_coords_are_kept() {
+        let model = load_model(&fixture("cube_seams.obj")).unwrap();
+        // the two faces share an edge, but with different texture coordinates
+        assert_eq!(model.vertices.len(), 8);
+        assert_eq!(model.indices.len(), 12);
+        assert_eq!(model.texture, None);
+    }
+
+    #[test]
+    fn polygons_are_triangulated() {
+        let obj = parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0.5 2 0\nv 0 1 0\nf 1 2 3 4 5\n").unwrap();
+        assert_eq!(obj.vertices.len(), 5);
+        assert_eq!(obj.indices, vec![0, 1, 2, 0, 2, 3, 0, 3, 4]);
+    }
+
+    #[test]
+    fn negative_indices_are_relative() {
+        let obj = parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nf -3 -2 -1\n").unwrap();
+        assert_eq!(obj.indices, vec![0, 1, 2]);
+        assert_eq!(obj.vertices[2].position, [1.0, 1.0, 0.0]);
+    }
+
+    #[test]
+    fn normals_are_ignored() {
+        let obj = parse("v 0 0 0\nv 1 0 0\nv 1 1 0\nvn 0 0 1\nf 1//1 2//1 3//1\n").unwrap();
+        assert_eq!(obj.vertices.len(), 3);
+        assert_eq!(obj.vertices[0].tex_coord, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn index_out_of_range_is_an_error() {
+        match parse("v 0 0 0\nv 1 0 0\nf 1 2 3\n") {
+            Err(ObjError::Parse { line, .. }) => assert_eq!(line, 3),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn invalid_number_is_an_error() {
+        match parse("v 0 zero 0\n") {
+            Err(ObjError::Parse { line, .. }) => assert_eq!(line, 1),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn materials_are_read() {
+        let materials = parse_mtl(Cursor::new("newmtl plain\nKd 1 1 1\nnewmtl wood\nmap_Kd -bm 0.5 textures/wood.png\n")).unwrap();
+        assert_eq!(materials, vec![
+            Material { name: "plain".to_owned(), diffuse_texture: None },
+            Material { name: "wood".to_owned(), diffuse_texture: Some("textures/wood.png".to_owned()) },
+        ]);
+    }
+}
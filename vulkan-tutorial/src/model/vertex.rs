@@ -0,0 +1,9 @@
+/// Textured vertex with a position in 3D space, the member names must match the shader inputs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TexturedVertex3d {
+    pub position: [f32; 3],
+    pub color: [f32; 3],
+    pub tex_coord: [f32; 2],
+}
+
+impl_vertex!(TexturedVertex3d, position, color, tex_coord);
@@ -0,0 +1,110 @@
+use vulkano::instance::{Features, PhysicalDevice};
+use vulkano::framebuffer::RenderPass;
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+
+use clap::Arg;
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use util::app::{Scene, Frame};
+use util::render_pass::CustomRenderPassDesc;
+use depth::depth_buffering::MeshScene;
+use model::loading_models::{model_arg, load_textured_model};
+use mipmap::generating_mipmaps::{create_mipmapped_texture_image, create_mipmap_sampler, mip_levels};
+use texture::texture_mapping::texture_arg;
+
+use std::sync::Arc;
+
+/// Sample counts that can be chosen on the command line, the highest first
+const SAMPLE_COUNTS: [u32; 4] = [8, 4, 2, 1];
+
+pub struct Multisampling;
+
+impl Tutorial for Multisampling {
+    fn name(&self) -> &'static str {
+        "msaa"
+    }
+
+    fn description(&self) -> &'static str {
+        "Multisample anti-aliasing"
+    }
+
+    fn args(&self) -> Vec<Arg<'static, 'static>> {
+        vec![
+            model_arg(),
+            texture_arg(),
+            Arg::with_name("samples")
+                .long("samples")
+                .takes_value(true)
+                .value_name("N")
+                .possible_values(&["1", "2", "4", "8"])
+                .help("Samples per pixel, defaults to the maximum the device supports"),
+        ]
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        let (model, image) = load_textured_model(config)?;
+        // the possible values are checked by clap
+        let requested_samples = config.value_of("samples").map(|samples| samples.parse::<u32>().unwrap());
+
+        let features = Features {
+            sampler_anisotropy: true,
+            .. Features::none()
+        };
+        app::try_run_with_features("Multisampling", config, &features, move |device, queues| {
+            let max_samples = max_usable_sample_count(device.physical_device());
+            let samples = match requested_samples {
+                Some(samples) if samples > max_samples => {
+                    return Err(Error::InvalidArgument(format!("samples must be at most {} for this device, got {}",
+                                                              max_samples, samples)));
+                },
+                Some(samples) => samples,
+                None => max_samples,
+            };
+            println!("Rendering with {} samples per pixel", samples);
+
+            let texture = create_mipmapped_texture_image(&queues.graphics, &image);
+            let sampler = create_mipmap_sampler(device, 0.0, mip_levels(image.width(), image.height()) as f32)?;
+            Ok(MultisampledScene {
+                scene: MeshScene::new(device, queues, &model.vertices, &model.indices, texture, sampler),
+                samples: samples,
+            })
+        })
+    }
+}
+
+/// The highest sample count that can be used for both the color and the depth attachment
+pub fn max_usable_sample_count(physical_device: PhysicalDevice) -> u32 {
+    let limits = physical_device.limits();
+    // the limits are bit masks with one bit for each supported sample count
+    let counts = limits.framebuffer_color_sample_counts() & limits.framebuffer_depth_sample_counts();
+    SAMPLE_COUNTS.iter()
+        .cloned()
+        .find(|&samples| counts & samples != 0)
+        .unwrap_or(1)
+}
+
+/// The mesh of the previous chapters drawn with multiple samples per pixel
+struct MultisampledScene {
+    scene: MeshScene,
+    samples: u32,
+}
+
+impl Scene for MultisampledScene {
+    fn create_pipeline(&mut self, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, dimensions: [u32; 2]) {
+        // the pipeline takes the sample count from the subpass
+        self.scene.create_pipeline(render_pass, dimensions);
+    }
+
+    fn draw(&mut self, builder: AutoCommandBufferBuilder, frame: &Frame) -> AutoCommandBufferBuilder {
+        self.scene.draw(builder, frame)
+    }
+
+    fn depth_buffer(&self) -> bool {
+        self.scene.depth_buffer()
+    }
+
+    fn samples(&self) -> u32 {
+        self.samples
+    }
+}
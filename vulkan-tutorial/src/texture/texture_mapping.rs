@@ -0,0 +1,235 @@
+use vulkano::instance::Features;
+use vulkano::device::{Device, Queue};
+use vulkano::buffer::{BufferUsage, CpuBufferPool, ImmutableBuffer};
+use vulkano::format::Format;
+use vulkano::image::{Dimensions, ImmutableImage};
+use vulkano::sampler::{Sampler, Filter, MipmapMode, SamplerAddressMode};
+use vulkano::framebuffer::{Subpass, RenderPass};
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::descriptor::PipelineLayoutAbstract;
+use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
+use vulkano::pipeline::GraphicsPipeline;
+use vulkano::pipeline::vertex::SingleBufferDefinition;
+use vulkano::pipeline::viewport::{Viewport, Scissor};
+use vulkano::sync::GpuFuture;
+
+use clap::Arg;
+
+use image;
+use image::RgbaImage;
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use util::app::{Scene, Frame, MAX_FRAMES_IN_FLIGHT};
+use util::device::Queues;
+use util::render_pass::CustomRenderPassDesc;
+use vertex::staging_buffer::upload_buffer;
+use vertex::index_buffer::{Indices, INDICES};
+use uniform::uniform_buffers::{UniformBufferObject, update_uniform_buffer};
+
+use std::path::Path;
+use std::sync::Arc;
+
+/// Used if no texture is given on the command line
+const DEFAULT_TEXTURE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/textures/texture.png");
+
+/// Vertex with texture coordinates, the member names must match the shader inputs
+#[derive(Debug, Clone, Copy)]
+pub struct TexturedVertex {
+    pub position: [f32; 2],
+    pub color: [f32; 3],
+    pub tex_coord: [f32; 2],
+}
+
+impl_vertex!(TexturedVertex, position, color, tex_coord);
+
+const VERTICES: [TexturedVertex; 4] = [
+    TexturedVertex { position: [-0.5, -0.5], color: [1.0, 0.0, 0.0], tex_coord: [1.0, 0.0] },
+    TexturedVertex { position: [0.5, -0.5], color: [0.0, 1.0, 0.0], tex_coord: [0.0, 0.0] },
+    TexturedVertex { position: [0.5, 0.5], color: [0.0, 0.0, 1.0], tex_coord: [0.0, 1.0] },
+    TexturedVertex { position: [-0.5, 0.5], color: [1.0, 1.0, 1.0], tex_coord: [1.0, 1.0] },
+];
+
+type TexturePipeline = GraphicsPipeline<SingleBufferDefinition<TexturedVertex>, Box<PipelineLayoutAbstract + Send + Sync>, Arc<RenderPass<CustomRenderPassDesc>>>;
+
+#[allow(unused)]
+pub mod vs {
+    #[derive(VulkanoShader)]
+    #[ty = "vertex"]
+    #[path = "src/texture/shader.vert"]
+    struct Dummy;
+}
+
+#[allow(unused)]
+pub mod fs {
+    #[derive(VulkanoShader)]
+    #[ty = "fragment"]
+    #[path = "src/texture/shader.frag"]
+    struct Dummy;
+}
+
+pub struct TextureMapping;
+
+impl Tutorial for TextureMapping {
+    fn name(&self) -> &'static str {
+        "texture_mapping"
+    }
+
+    fn description(&self) -> &'static str {
+        "Texture mapping"
+    }
+
+    fn args(&self) -> Vec<Arg<'static, 'static>> {
+        vec![
+            texture_arg(),
+        ]
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        let image = load_image(&texture_path(config))?;
+
+        // anisotropic filtering is optional, it is only enabled if the device supports it
+        let features = Features {
+            sampler_anisotropy: true,
+            .. Features::none()
+        };
+        app::run_with_features("Texture Mapping", config, &features, move |device, queues| {
+            TextureScene::new(device, queues, &image)
+        })
+    }
+}
+
+/// The argument for the image file of the texture
+pub fn texture_arg() -> Arg<'static, 'static> {
+    Arg::with_name("texture")
+        .long("texture")
+        .takes_value(true)
+        .value_name("PATH")
+        .help("PNG or JPEG file used as texture")
+}
+
+pub fn texture_path<'a>(config: &'a Config) -> &'a Path {
+    Path::new(config.value_of("texture").unwrap_or(DEFAULT_TEXTURE))
+}
+
+/// Draws the rotating rectangle with a texture
+struct TextureScene {
+    device: Arc<Device>,
+    vertex_buffer: Arc<ImmutableBuffer<[TexturedVertex]>>,
+    indices: Indices,
+    uniform_buffers: Vec<CpuBufferPool<UniformBufferObject>>,
+    texture: Arc<ImmutableImage<Format>>,
+    sampler: Arc<Sampler>,
+    pipeline: Option<Arc<TexturePipeline>>,
+    aspect_ratio: f32,
+}
+
+impl TextureScene {
+    fn new(device: &Arc<Device>, queues: &Queues, image: &RgbaImage) -> TextureScene {
+        let uniform_buffers = (0..MAX_FRAMES_IN_FLIGHT)
+            .map(|_| CpuBufferPool::new(device.clone(), BufferUsage::uniform_buffer()))
+            .collect();
+
+        TextureScene {
+            device: device.clone(),
+            vertex_buffer: upload_buffer(device, &queues.transfer, VERTICES.iter().cloned(), BufferUsage::vertex_buffer()),
+            indices: Indices::new(device, &queues.transfer, &INDICES),
+            uniform_buffers: uniform_buffers,
+            texture: create_texture_image(&queues.graphics, image),
+            sampler: create_texture_sampler(device),
+            pipeline: None,
+            aspect_ratio: 1.0,
+        }
+    }
+}
+
+impl Scene for TextureScene {
+    fn create_pipeline(&mut self, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, dimensions: [u32; 2]) {
+        self.pipeline = Some(create_graphics_pipeline(&self.device, dimensions, render_pass));
+        self.aspect_ratio = dimensions[0] as f32 / dimensions[1] as f32;
+    }
+
+    fn draw(&mut self, builder: AutoCommandBufferBuilder, frame: &Frame) -> AutoCommandBufferBuilder {
+        let pipeline = self.pipeline.clone().unwrap();
+
+        let uniform_buffer = self.uniform_buffers[frame.index]
+            .next(update_uniform_buffer(frame.time, self.aspect_ratio)).unwrap();
+        let descriptor_set = Arc::new(PersistentDescriptorSet::start(pipeline.clone(), 0)
+            .add_buffer(uniform_buffer).unwrap()
+            .add_sampled_image(self.texture.clone(), self.sampler.clone()).unwrap()
+            .build().unwrap());
+
+        self.indices.draw(builder, pipeline, self.vertex_buffer.clone(), descriptor_set)
+    }
+}
+
+pub fn load_image(path: &Path) -> Result<RgbaImage, Error> {
+    Ok(image::open(path)?.to_rgba())
+}
+
+/// Upload the pixels into an image the shaders can sample from.
+/// The upload uses the graphics queue, because it also transitions the image into the layout for sampling.
+pub fn create_texture_image(queue: &Arc<Queue>, image: &RgbaImage) -> Arc<ImmutableImage<Format>> {
+    let (width, height) = image.dimensions();
+    let dimensions = Dimensions::Dim2d { width: width, height: height };
+
+    let (texture, upload) = ImmutableImage::from_iter(image.iter().cloned(), dimensions,
+                                                      Format::R8G8B8A8Srgb, queue.clone()).unwrap();
+    upload.then_signal_fence_and_flush().unwrap()
+        .wait(None).unwrap();
+
+    texture
+}
+
+/// Sampler with linear filtering, anisotropic filtering is used if the feature is enabled
+pub fn create_texture_sampler(device: &Arc<Device>) -> Arc<Sampler> {
+    Sampler::new(device.clone(),
+                 Filter::Linear,
+                 Filter::Linear,
+                 MipmapMode::Linear,
+                 SamplerAddressMode::Repeat,
+                 SamplerAddressMode::Repeat,
+                 SamplerAddressMode::Repeat,
+                 0.0, // mip_lod_bias
+                 max_anisotropy(device),
+                 0.0, // min_lod
+                 0.0) // max_lod
+        .unwrap()
+}
+
+/// The anisotropy used for sampling, 1.0 disables anisotropic filtering
+pub fn max_anisotropy(device: &Arc<Device>) -> f32 {
+    if device.enabled_features().sampler_anisotropy {
+        device.physical_device().limits().max_sampler_anisotropy().min(16.0)
+    }
+    else {
+        1.0
+    }
+}
+
+fn create_graphics_pipeline(device: &Arc<Device>, dimensions: [u32; 2],
+        render_pass: &Arc<RenderPass<CustomRenderPassDesc>>) -> Arc<TexturePipeline> {
+    let vs = vs::Shader::load(device.clone()).expect("failed to create shader module");
+    let fs = fs::Shader::load(device.clone()).expect("failed to create shader module");
+
+    let viewport = Viewport {
+        origin: [0.0, 0.0],
+        dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+        depth_range: 0.0 .. 1.0,
+    };
+
+    let scissor = Scissor {
+        origin: [0,0],
+        dimensions: dimensions,
+    };
+
+    Arc::new(GraphicsPipeline::start()
+        .vertex_input_single_buffer::<TexturedVertex>()
+        .vertex_shader(vs.main_entry_point(), ())
+        .triangle_list()
+        .viewports_scissors(Some((viewport, scissor)))
+        .fragment_shader(fs.main_entry_point(), ())
+        .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+        .build(device.clone())
+        .unwrap())
+}
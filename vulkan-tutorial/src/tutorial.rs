@@ -0,0 +1,201 @@
+use clap::{Arg, ArgMatches};
+
+use image::ImageError;
+
+use vulkano::buffer::cpu_access::ReadLockError;
+use vulkano::command_buffer::CopyBufferImageError;
+use vulkano::sampler::SamplerCreationError;
+
+use model::obj::ObjError;
+
+use std::error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+use std::slice;
+
+/// A chapter of the tutorial which can be started from the command line
+pub trait Tutorial {
+    /// Name of the sub command running the tutorial
+    fn name(&self) -> &'static str;
+
+    /// Short description shown in the help and in the list of tutorials
+    fn description(&self) -> &'static str;
+
+    /// Command line arguments in addition to the ones every tutorial accepts
+    fn args(&self) -> Vec<Arg<'static, 'static>> {
+        Vec::new()
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error>;
+}
+
+/// The command line options a tutorial is started with
+pub struct Config<'a> {
+    pub headless: bool,
+    pub frames: Option<u32>,
+    pub screenshot: Option<PathBuf>,
+    matches: &'a ArgMatches<'a>,
+}
+
+impl<'a> Config<'a> {
+    pub fn from_matches(matches: &'a ArgMatches<'a>) -> Result<Config<'a>, Error> {
+        let frames = match matches.value_of("frames") {
+            Some(frames) => Some(frames.parse::<u32>()
+                .map_err(|_| Error::InvalidArgument(format!("frames must be a number, got '{}'", frames)))?),
+            None => None,
+        };
+
+        Ok(Config {
+            headless: matches.is_present("headless"),
+            frames: frames,
+            screenshot: matches.value_of("screenshot").map(PathBuf::from),
+            matches: matches,
+        })
+    }
+
+    /// Value of an argument added by `Tutorial::args`
+    pub fn value_of(&self, name: &str) -> Option<&str> {
+        self.matches().value_of(name)
+    }
+
+    /// All arguments the tutorial was started with
+    pub fn matches(&self) -> &'a ArgMatches<'a> {
+        self.matches
+    }
+}
+
+/// Errors reported by a tutorial
+#[derive(Debug)]
+pub enum Error {
+    InvalidArgument(String),
+    /// The command line names no or an unknown command
+    UnknownCommand(String),
+    /// No frame was rendered that could be saved as screenshot
+    NoFrameRendered,
+    /// The surface does not allow copying its images, so no screenshot can be taken
+    ScreenshotUnsupported,
+    /// The rendered image could not be copied for a screenshot
+    CopyImage(CopyBufferImageError),
+    /// The copied pixels could not be read back
+    ReadPixels(ReadLockError),
+    Sampler(SamplerCreationError),
+    Io(io::Error),
+    /// An image could not be loaded
+    Image(ImageError),
+    /// A model could not be loaded
+    Model(ObjError),
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<CopyBufferImageError> for Error {
+    fn from(err: CopyBufferImageError) -> Error {
+        Error::CopyImage(err)
+    }
+}
+
+impl From<ReadLockError> for Error {
+    fn from(err: ReadLockError) -> Error {
+        Error::ReadPixels(err)
+    }
+}
+
+impl From<SamplerCreationError> for Error {
+    fn from(err: SamplerCreationError) -> Error {
+        Error::Sampler(err)
+    }
+}
+
+impl From<ImageError> for Error {
+    fn from(err: ImageError) -> Error {
+        Error::Image(err)
+    }
+}
+
+impl From<ObjError> for Error {
+    fn from(err: ObjError) -> Error {
+        Error::Model(err)
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        match *self {
+            Error::InvalidArgument(_) => "Invalid argument",
+            Error::UnknownCommand(_) => "Unknown command",
+            Error::NoFrameRendered => "No frame was rendered",
+            Error::ScreenshotUnsupported => "Screenshots are not supported by the surface",
+            Error::CopyImage(_) => "Could not copy the image",
+            Error::ReadPixels(_) => "Could not read the pixels",
+            Error::Sampler(_) => "Could not create the sampler",
+            Error::Io(_) => "I/O error",
+            Error::Image(_) => "Image error",
+            Error::Model(_) => "Model error",
+        }
+    }
+
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            Error::CopyImage(ref err) => Some(err),
+            Error::ReadPixels(ref err) => Some(err),
+            Error::Sampler(ref err) => Some(err),
+            Error::Io(ref err) => Some(err),
+            Error::Image(ref err) => Some(err),
+            Error::Model(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::InvalidArgument(ref msg) => write!(f, "Invalid argument: {}", msg),
+            Error::UnknownCommand(ref name) => write!(f, "Unknown command: {}", name),
+            Error::NoFrameRendered => write!(f, "No frame was rendered"),
+            Error::ScreenshotUnsupported => write!(f, "Screenshots are not supported by the surface, its images cannot be copied"),
+            Error::CopyImage(ref err) => write!(f, "Could not copy the image: {}", err),
+            Error::ReadPixels(ref err) => write!(f, "Could not read the pixels: {}", err),
+            Error::Sampler(ref err) => write!(f, "Could not create the sampler: {}", err),
+            Error::Io(ref err) => write!(f, "I/O error: {}", err),
+            Error::Image(ref err) => write!(f, "Image error: {}", err),
+            Error::Model(ref err) => write!(f, "Model error: {}", err),
+        }
+    }
+}
+
+/// The tutorials available on the command line, in the order they are listed
+pub struct Registry {
+    tutorials: Vec<Box<Tutorial>>,
+}
+
+impl Registry {
+    pub fn new() -> Registry {
+        Registry {
+            tutorials: Vec::new(),
+        }
+    }
+
+    pub fn register<T: Tutorial + 'static>(&mut self, tutorial: T) {
+        self.tutorials.push(Box::new(tutorial));
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Tutorial> {
+        self.tutorials.iter().find(|t| t.name() == name).map(|t| t.as_ref())
+    }
+
+    pub fn iter(&self) -> slice::Iter<Box<Tutorial>> {
+        self.tutorials.iter()
+    }
+}
+
+impl Default for Registry {
+    fn default() -> Registry {
+        Registry::new()
+    }
+}
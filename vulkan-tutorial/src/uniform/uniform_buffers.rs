@@ -0,0 +1,146 @@
+use vulkano::device::Device;
+use vulkano::buffer::{BufferUsage, CpuBufferPool, ImmutableBuffer};
+use vulkano::framebuffer::{Subpass, RenderPass};
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
+use vulkano::pipeline::GraphicsPipeline;
+use vulkano::pipeline::viewport::{Viewport, Scissor};
+
+use cgmath;
+use cgmath::{Matrix4, Point3, Vector3, Deg};
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use util::app::{Scene, Frame, MAX_FRAMES_IN_FLIGHT};
+use util::device::Queues;
+use util::render_pass::CustomRenderPassDesc;
+use vertex::vertex_buffers::{Vertex, VertexPipeline};
+use vertex::staging_buffer::upload_buffer;
+use vertex::index_buffer::{Indices, VERTICES, INDICES};
+
+use std::sync::Arc;
+
+#[allow(unused)]
+pub mod vs {
+    #[derive(VulkanoShader)]
+    #[ty = "vertex"]
+    #[path = "src/uniform/shader.vert"]
+    struct Dummy;
+}
+
+#[allow(unused)]
+pub mod fs {
+    #[derive(VulkanoShader)]
+    #[ty = "fragment"]
+    #[path = "src/vertex/shader.frag"]
+    struct Dummy;
+}
+
+pub use self::vs::ty::UniformBufferObject;
+
+pub struct UniformBuffers;
+
+impl Tutorial for UniformBuffers {
+    fn name(&self) -> &'static str {
+        "uniform_buffers"
+    }
+
+    fn description(&self) -> &'static str {
+        "Uniform buffers"
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        app::run("Uniform Buffers", config, UniformBufferScene::new)
+    }
+}
+
+/// Draws the rectangle rotating around the z axis, the transformation is passed to the
+/// vertex shader in a uniform buffer
+struct UniformBufferScene {
+    device: Arc<Device>,
+    vertex_buffer: Arc<ImmutableBuffer<[Vertex]>>,
+    indices: Indices,
+    /// One pool per frame in flight, so a frame never writes a buffer the GPU still reads
+    uniform_buffers: Vec<CpuBufferPool<UniformBufferObject>>,
+    pipeline: Option<Arc<VertexPipeline>>,
+    aspect_ratio: f32,
+}
+
+impl UniformBufferScene {
+    fn new(device: &Arc<Device>, queues: &Queues) -> UniformBufferScene {
+        let uniform_buffers = (0..MAX_FRAMES_IN_FLIGHT)
+            .map(|_| CpuBufferPool::new(device.clone(), BufferUsage::uniform_buffer()))
+            .collect();
+
+        UniformBufferScene {
+            device: device.clone(),
+            vertex_buffer: upload_buffer(device, &queues.transfer, VERTICES.iter().cloned(), BufferUsage::vertex_buffer()),
+            indices: Indices::new(device, &queues.transfer, &INDICES),
+            uniform_buffers: uniform_buffers,
+            pipeline: None,
+            aspect_ratio: 1.0,
+        }
+    }
+}
+
+impl Scene for UniformBufferScene {
+    fn create_pipeline(&mut self, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, dimensions: [u32; 2]) {
+        self.pipeline = Some(create_graphics_pipeline(&self.device, dimensions, render_pass));
+        self.aspect_ratio = dimensions[0] as f32 / dimensions[1] as f32;
+    }
+
+    fn draw(&mut self, builder: AutoCommandBufferBuilder, frame: &Frame) -> AutoCommandBufferBuilder {
+        let pipeline = self.pipeline.clone().unwrap();
+
+        let uniform_buffer = self.uniform_buffers[frame.index]
+            .next(update_uniform_buffer(frame.time, self.aspect_ratio)).unwrap();
+        let descriptor_set = Arc::new(PersistentDescriptorSet::start(pipeline.clone(), 0)
+            .add_buffer(uniform_buffer).unwrap()
+            .build().unwrap());
+
+        self.indices.draw(builder, pipeline, self.vertex_buffer.clone(), descriptor_set)
+    }
+}
+
+/// Rotate the model by 90 degrees per second and look at it from above
+pub fn update_uniform_buffer(time: f64, aspect_ratio: f32) -> UniformBufferObject {
+    let model = Matrix4::from_angle_z(Deg(90.0 * time as f32));
+    let view = Matrix4::look_at(Point3::new(2.0, 2.0, 2.0), Point3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 0.0, 1.0));
+    let mut proj = cgmath::perspective(Deg(45.0), aspect_ratio, 0.1, 10.0);
+    // the projection is made for OpenGL, where the y axis of the clip coordinates points up
+    proj.y.y *= -1.0;
+
+    UniformBufferObject {
+        model: model.into(),
+        view: view.into(),
+        proj: proj.into(),
+    }
+}
+
+/// The vertex shader now reads the transformation from the uniform buffer at binding 0
+fn create_graphics_pipeline(device: &Arc<Device>, dimensions: [u32; 2],
+        render_pass: &Arc<RenderPass<CustomRenderPassDesc>>) -> Arc<VertexPipeline> {
+    let vs = vs::Shader::load(device.clone()).expect("failed to create shader module");
+    let fs = fs::Shader::load(device.clone()).expect("failed to create shader module");
+
+    let viewport = Viewport {
+        origin: [0.0, 0.0],
+        dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+        depth_range: 0.0 .. 1.0,
+    };
+
+    let scissor = Scissor {
+        origin: [0,0],
+        dimensions: dimensions,
+    };
+
+    Arc::new(GraphicsPipeline::start()
+        .vertex_input_single_buffer::<Vertex>()
+        .vertex_shader(vs.main_entry_point(), ())
+        .triangle_list()
+        .viewports_scissors(Some((viewport, scissor)))
+        .fragment_shader(fs.main_entry_point(), ())
+        .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+        .build(device.clone())
+        .unwrap())
+}
@@ -0,0 +1,386 @@
+use glfw::Glfw;
+
+use vulkano::instance::{InstanceExtensions, Instance, Features, DeviceExtensions};
+use vulkano::instance::debug::DebugCallback;
+use vulkano::device::{Device, Queue};
+use vulkano::swapchain;
+use vulkano::swapchain::{Surface, Swapchain, AcquireError, SwapchainCreationError};
+use vulkano::format::Format;
+use vulkano::image::{SwapchainImage, ImageLayout};
+use vulkano::sync;
+use vulkano::sync::{GpuFuture, FlushError};
+use vulkano::framebuffer::{FramebufferAbstract, RenderPass};
+use vulkano::command_buffer::AutoCommandBufferBuilder;
+
+use vulkano_glfw as vg;
+use vulkano_glfw::GlfwWindow;
+
+use tutorial::{Config, Error};
+use util::device::{Queues, pick_physical_device, create_logical_device};
+use util::format::find_depth_format;
+use util::frames::FramesInFlight;
+use util::headless;
+use util::headless::OffscreenTarget;
+use util::instance::{create_instance, setup_debug_callback};
+use util::render_pass::{CustomRenderPassDesc, create_render_pass, create_framebuffer};
+use util::screenshot::Screenshot;
+use util::swapchain::{init_window, create_surface, create_swap_chain, create_framebuffers, query_swap_chain_support};
+
+use std::sync::Arc;
+use std::path::Path;
+
+const WIDTH: u32 = 800;
+const HEIGHT: u32 = 600;
+
+pub const MAX_FRAMES_IN_FLIGHT: usize = 2;
+
+/// Frames rendered per second of animation time when running headless
+const HEADLESS_FRAME_RATE: f64 = 60.0;
+
+/// The part of a tutorial that differs between the chapters.
+///
+/// Window, swapchain, render pass and frame pacing are handled by `run`,
+/// the scene only creates its pipeline and records what it draws.
+pub trait Scene {
+    /// Create the pipeline for the render pass, called again whenever the swapchain was recreated
+    fn create_pipeline(&mut self, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, dimensions: [u32; 2]);
+
+    /// Record the commands drawing one frame, the render pass has already been started
+    fn draw(&mut self, builder: AutoCommandBufferBuilder, frame: &Frame) -> AutoCommandBufferBuilder;
+
+    /// Whether the render pass gets a depth attachment for depth testing
+    fn depth_buffer(&self) -> bool {
+        false
+    }
+
+    /// Number of samples per pixel, with more than one sample the image is resolved at the end of the render pass
+    fn samples(&self) -> u32 {
+        1
+    }
+
+    /// Submit work the frame depends on, e.g. a compute pass on another queue.
+    /// The commands recorded by `draw` wait for the returned future.
+    fn prepare_frame(&mut self, future: Box<GpuFuture>, _frame: &Frame) -> Box<GpuFuture> {
+        future
+    }
+}
+
+/// The frame a scene records its commands for
+pub struct Frame {
+    /// Slot of the frame in flight, below `MAX_FRAMES_IN_FLIGHT`. Resources used by the
+    /// frame are not used by the GPU anymore when the slot comes around again.
+    pub index: usize,
+    /// Seconds since the start of the application, used for animations
+    pub time: f64,
+}
+
+/// Run a scene in a window or offscreen, depending on the configuration.
+/// The scene is created from the device and its queues.
+pub fn run<S, F>(application_name: &str, config: &Config, create_scene: F) -> Result<(), Error>
+    where S: Scene,
+          F: FnOnce(&Arc<Device>, &Queues) -> S
+{
+    run_with_features(application_name, config, &Features::none(), create_scene)
+}
+
+/// Like `run`, but enables the given device features if the device supports them
+pub fn run_with_features<S, F>(application_name: &str, config: &Config, features: &Features, create_scene: F) -> Result<(), Error>
+    where S: Scene,
+          F: FnOnce(&Arc<Device>, &Queues) -> S
+{
+    try_run_with_features(application_name, config, features, |device, queues| Ok(create_scene(device, queues)))
+}
+
+/// Like `run_with_features`, for scenes whose creation fails, e.g. on arguments the device does not support
+pub fn try_run_with_features<S, F>(application_name: &str, config: &Config, features: &Features, create_scene: F) -> Result<(), Error>
+    where S: Scene,
+          F: FnOnce(&Arc<Device>, &Queues) -> Result<S, Error>
+{
+    let screenshot = config.screenshot.as_ref().map(|path| path.as_path());
+
+    if config.headless {
+        HeadlessApplication::new(application_name, features, create_scene)?.run(config.frames.unwrap_or(1), screenshot)
+    }
+    else {
+        // the screenshot is taken of the last frame, so there has to be one
+        let frames = if screenshot.is_some() { Some(config.frames.unwrap_or(1)) } else { config.frames };
+        WindowApplication::new(application_name, features, create_scene)?.run(frames, screenshot)
+    }
+}
+
+fn begin_frame(builder: AutoCommandBufferBuilder, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>,
+        framebuffer: Arc<FramebufferAbstract + Send + Sync>) -> AutoCommandBufferBuilder {
+    builder.begin_render_pass(framebuffer, false, render_pass.desc().clear_values()).unwrap()
+}
+
+/// The depth format for the render pass if the scene uses depth testing
+fn depth_format<S: Scene>(scene: &S, device: &Arc<Device>) -> Option<Format> {
+    if scene.depth_buffer() {
+        Some(find_depth_format(device.physical_device()))
+    }
+    else {
+        None
+    }
+}
+
+struct WindowApplication<S> {
+    glfw: Glfw,
+    _instance: Arc<Instance>,
+    _callback: Option<DebugCallback>,
+    device: Arc<Device>,
+    graphics_queue: Arc<Queue>,
+    present_queue: Arc<Queue>,
+    surface: Arc<Surface<GlfwWindow>>,
+    swapchain: Arc<Swapchain<GlfwWindow>>,
+    images: Vec<Arc<SwapchainImage<GlfwWindow>>>,
+    render_pass: Arc<RenderPass<CustomRenderPassDesc>>,
+    framebuffers: Vec<Arc<FramebufferAbstract + Send + Sync>>,
+    frames_in_flight: FramesInFlight,
+    screenshot: Option<Screenshot>,
+    scene: S,
+}
+
+impl<S: Scene> WindowApplication<S> {
+    fn run(&mut self, max_frames: Option<u32>, screenshot: Option<&Path>) -> Result<(), Error> {
+        if screenshot.is_some() && !self.supports_screenshots() {
+            return Err(Error::ScreenshotUnsupported);
+        }
+        self.main_loop(max_frames, screenshot.is_some())?;
+        if let Some(path) = screenshot {
+            self.save_screenshot(path)?;
+        }
+        Ok(())
+    }
+
+    /// The swapchain images can only be copied if the surface supports it
+    fn supports_screenshots(&self) -> bool {
+        query_swap_chain_support(&self.surface, self.device.physical_device()).supported_usage_flags.transfer_source
+    }
+
+    fn main_loop(&mut self, max_frames: Option<u32>, take_screenshot: bool) -> Result<(), Error> {
+        let mut frame_count = 0;
+        while !self.surface.window().should_close().unwrap() && max_frames.map_or(true, |max| frame_count < max) {
+            if self.surface.window().is_iconified() {
+                // nothing is drawn while the window is minimised, so sleep until it is restored
+                self.glfw.wait_events();
+            }
+            else {
+                self.glfw.poll_events();
+            }
+            for (_, _event) in self.surface.window().poll_events().unwrap() {
+                // events are not used yet, but the queue must be drained
+            }
+            let capture = take_screenshot && max_frames == Some(frame_count + 1);
+            if self.draw_frame(capture)? {
+                frame_count += 1;
+            }
+        }
+        self.frames_in_flight.wait_idle();
+        Ok(())
+    }
+
+    fn save_screenshot(&mut self, path: &Path) -> Result<(), Error> {
+        match self.screenshot.take() {
+            Some(screenshot) => {
+                screenshot.save(path)?;
+                println!("Saved screenshot to {}", path.display());
+                Ok(())
+            },
+            None => Err(Error::NoFrameRendered),
+        }
+    }
+
+    /// Render and present one frame, returns `false` if no frame could be presented.
+    /// If `capture` is set, the rendered image is also copied for a screenshot.
+    fn draw_frame(&mut self, capture: bool) -> Result<bool, Error> {
+        if self.surface.window().is_iconified() {
+            return Ok(false);
+        }
+
+        if self.surface.window().is_surface_out_of_date() && !self.recreate_swap_chain() {
+            return Ok(false);
+        }
+
+        let frame_start = self.frames_in_flight.begin_frame();
+
+        let (image_num, acquire_future) = match swapchain::acquire_next_image(self.swapchain.clone(), None) {
+            Ok(r) => r,
+            Err(AcquireError::OutOfDate) => {
+                self.surface.window().set_surface_out_of_date(true);
+                return Ok(false);
+            },
+            Err(err) => panic!("{:?}", err),
+        };
+
+        let frame = Frame {
+            index: self.frames_in_flight.current_frame(),
+            time: self.glfw.get_time(),
+        };
+        let builder = AutoCommandBufferBuilder::primary_one_time_submit(self.device.clone(), self.graphics_queue.family()).unwrap();
+        let builder = begin_frame(builder, &self.render_pass, self.framebuffers[image_num].clone());
+        let mut builder = self.scene.draw(builder, &frame).end_render_pass().unwrap();
+        if capture {
+            let screenshot = Screenshot::new(&self.device, self.swapchain.dimensions(), self.swapchain.format());
+            builder = screenshot.copy_from_image(builder, self.images[image_num].clone())?;
+            self.screenshot = Some(screenshot);
+        }
+        let command_buffer = builder.build().unwrap();
+
+        let future = self.scene.prepare_frame(frame_start, &frame)
+            .join(acquire_future)
+            .then_execute(self.graphics_queue.clone(), command_buffer).unwrap()
+            .then_swapchain_present(self.present_queue.clone(), self.swapchain.clone(), image_num);
+
+        match self.frames_in_flight.end_frame(future) {
+            Ok(()) => Ok(true),
+            Err(FlushError::OutOfDate) => {
+                self.surface.window().set_surface_out_of_date(true);
+                Ok(false)
+            },
+            Err(err) => panic!("{:?}", err),
+        }
+    }
+
+    /// Rebuild everything depending on the swapchain after the surface changed.
+    /// Returns `false` if the surface currently has no valid size for a swapchain.
+    fn recreate_swap_chain(&mut self) -> bool {
+        let (swapchain, images) = match create_swap_chain(&self.device, &self.surface, &self.graphics_queue, Some(&self.swapchain)) {
+            Ok(r) => r,
+            Err(SwapchainCreationError::UnsupportedDimensions) => return false,
+            Err(err) => panic!("{:?}", err),
+        };
+
+        let render_pass = {
+            let desc = self.render_pass.desc();
+            create_render_pass(&self.device, swapchain.format(), ImageLayout::PresentSrc, desc.depth_format(), desc.samples())
+        };
+        self.scene.create_pipeline(&render_pass, swapchain.dimensions());
+        self.framebuffers = create_framebuffers(&render_pass, &images);
+        self.render_pass = render_pass;
+        self.swapchain = swapchain;
+        self.images = images;
+        self.surface.window().set_surface_out_of_date(false);
+        true
+    }
+
+    fn new<F>(application_name: &str, features: &Features, create_scene: F) -> Result<WindowApplication<S>, Error>
+        where F: FnOnce(&Arc<Device>, &Queues) -> Result<S, Error>
+    {
+        let (glfw, window) = init_window(WIDTH, HEIGHT);
+
+        let instance = create_instance(application_name, vg::get_required_instance_extensions(&glfw).unwrap());
+        let callback = setup_debug_callback(&instance);
+
+        let surface = create_surface(&instance, window);
+
+        let req_dev_exts = DeviceExtensions {
+            khr_swapchain: true,
+            .. DeviceExtensions::none()
+        };
+
+        let physical_device = pick_physical_device(&glfw, &instance, &req_dev_exts, &surface).unwrap();
+        let (device, queues) = create_logical_device(&glfw, physical_device, &req_dev_exts, features);
+
+        let (swapchain, images) = create_swap_chain(&device, &surface, &queues.graphics, None).unwrap();
+
+        let mut scene = create_scene(&device, &queues)?;
+        let depth_format = depth_format(&scene, &device);
+        let render_pass = create_render_pass(&device, swapchain.format(), ImageLayout::PresentSrc, depth_format, scene.samples());
+        scene.create_pipeline(&render_pass, swapchain.dimensions());
+        let framebuffers = create_framebuffers(&render_pass, &images);
+        let frames_in_flight = FramesInFlight::new(device.clone(), MAX_FRAMES_IN_FLIGHT);
+
+        Ok(WindowApplication {
+            glfw: glfw,
+            _instance: instance.clone(),
+            _callback: callback,
+            device: device,
+            graphics_queue: queues.graphics.clone(),
+            present_queue: queues.present.clone(),
+            surface: surface,
+            swapchain: swapchain,
+            images: images,
+            render_pass: render_pass,
+            framebuffers: framebuffers,
+            frames_in_flight: frames_in_flight,
+            screenshot: None,
+            scene: scene,
+        })
+    }
+}
+
+/// Renders the scene into an offscreen image instead of a window
+struct HeadlessApplication<S> {
+    _instance: Arc<Instance>,
+    _callback: Option<DebugCallback>,
+    device: Arc<Device>,
+    queue: Arc<Queue>,
+    target: OffscreenTarget,
+    render_pass: Arc<RenderPass<CustomRenderPassDesc>>,
+    framebuffer: Arc<FramebufferAbstract + Send + Sync>,
+    scene: S,
+}
+
+impl<S: Scene> HeadlessApplication<S> {
+    fn run(&mut self, frames: u32, screenshot: Option<&Path>) -> Result<(), Error> {
+        for frame_number in 0..frames {
+            // the animation time does not depend on the speed of the machine, so the result can be compared
+            let frame = Frame {
+                index: 0,
+                time: frame_number as f64 / HEADLESS_FRAME_RATE,
+            };
+            let builder = AutoCommandBufferBuilder::primary_one_time_submit(self.device.clone(), self.queue.family()).unwrap();
+            let builder = begin_frame(builder, &self.render_pass, self.framebuffer.clone());
+            let builder = self.scene.draw(builder, &frame).end_render_pass().unwrap();
+            let command_buffer = self.target.copy_to_buffer(builder)?.build().unwrap();
+
+            // every frame writes the same image, so it has to be finished before the next one starts
+            self.scene.prepare_frame(Box::new(sync::now(self.device.clone())), &frame)
+                .then_execute(self.queue.clone(), command_buffer).unwrap()
+                .then_signal_fence_and_flush().unwrap()
+                .wait(None).unwrap();
+        }
+
+        match screenshot {
+            Some(path) => {
+                self.target.save_png(path)?;
+                println!("Saved screenshot to {}", path.display());
+            },
+            None => {
+                let dimensions = self.target.dimensions();
+                println!("Rendered {} frame(s) offscreen with {}x{} pixels", frames, dimensions[0], dimensions[1]);
+            },
+        }
+        Ok(())
+    }
+
+    fn new<F>(application_name: &str, features: &Features, create_scene: F) -> Result<HeadlessApplication<S>, Error>
+        where F: FnOnce(&Arc<Device>, &Queues) -> Result<S, Error>
+    {
+        let instance = create_instance(application_name, InstanceExtensions::none());
+        let callback = setup_debug_callback(&instance);
+
+        let physical_device = headless::pick_physical_device(&instance).unwrap();
+        let (device, queues) = headless::create_logical_device(physical_device, features);
+
+        let target = OffscreenTarget::new(&device, [WIDTH, HEIGHT], Format::R8G8B8A8Unorm);
+
+        let mut scene = create_scene(&device, &queues)?;
+        let depth_format = depth_format(&scene, &device);
+        // attachment images stay in the color attachment layout between uses
+        let render_pass = create_render_pass(&device, target.format(), ImageLayout::ColorAttachmentOptimal, depth_format, scene.samples());
+        scene.create_pipeline(&render_pass, target.dimensions());
+        let framebuffer = create_framebuffer(&render_pass, target.image().clone(), target.dimensions());
+
+        Ok(HeadlessApplication {
+            _instance: instance.clone(),
+            _callback: callback,
+            device: device,
+            queue: queues.graphics.clone(),
+            target: target,
+            render_pass: render_pass,
+            framebuffer: framebuffer,
+            scene: scene,
+        })
+    }
+}
@@ -0,0 +1,122 @@
+use glfw::Glfw;
+
+use vulkano::instance::{Instance, Features, PhysicalDevice, QueueFamily, DeviceExtensions};
+use vulkano::device::{Device, Queue};
+use vulkano::swapchain::{Surface, SupportedPresentModes};
+
+use vulkano_glfw as vg;
+use vulkano_glfw::GlfwWindow;
+
+use util::swapchain::query_swap_chain_support;
+
+use std::sync::Arc;
+
+pub fn pick_physical_device<'a>(glfw: &Glfw, instance: &'a Arc<Instance>, req_exts: &DeviceExtensions, surface: &Arc<Surface<GlfwWindow>>) -> Option<PhysicalDevice<'a>> {
+    for device in PhysicalDevice::enumerate(instance) {
+        if is_device_suitable(glfw, device, req_exts, surface) {
+            println!("Using device: {}", device.name());
+            return Some(device);
+        }
+    }
+    None
+}
+
+/// The queue families the tutorials create their queues from
+pub struct QueueFamilies<'a> {
+    pub graphics: QueueFamily<'a>,
+    /// A family only supporting transfers, if the device has one
+    pub transfer: Option<QueueFamily<'a>>,
+    /// A family supporting compute but not graphics, if the device has one
+    pub compute: Option<QueueFamily<'a>>,
+}
+
+/// The queues of the logical device
+pub struct Queues {
+    pub graphics: Arc<Queue>,
+    pub present: Arc<Queue>,
+    /// Queue of the dedicated transfer family, or the graphics queue if there is none
+    pub transfer: Arc<Queue>,
+    /// Queue of the dedicated compute family, or the graphics queue if there is none
+    pub compute: Arc<Queue>,
+}
+
+pub fn create_logical_device<'a>(glfw: &Glfw, phys: PhysicalDevice<'a>, req_exts: &DeviceExtensions,
+        features: &Features) -> (Arc<Device>, Queues) {
+    let families = find_queue_families(glfw, phys).unwrap();
+    create_device(phys, &families, req_exts, features)
+}
+
+/// Create the device with one queue of the graphics family and one of the transfer and compute families, if there are.
+/// Only the features supported by the physical device are enabled, see `Device::enabled_features`.
+pub fn create_device<'a>(phys: PhysicalDevice<'a>, families: &QueueFamilies<'a>, req_exts: &DeviceExtensions,
+        features: &Features) -> (Arc<Device>, Queues) {
+    let mut queue_families = vec![(families.graphics, 1.0)];
+    if let Some(transfer) = families.transfer {
+        queue_families.push((transfer, 1.0));
+    }
+    if let Some(compute) = families.compute {
+        queue_families.push((compute, 1.0));
+    }
+
+    let features = features.intersection(phys.supported_features());
+    let (device, mut qiter) = Device::new(phys, &features,
+                                req_exts,
+                                queue_families).unwrap();
+    // the queues are returned in the order of the families
+    let graphics = qiter.next().unwrap();
+    let transfer = match families.transfer {
+        Some(_) => qiter.next().unwrap(),
+        None => graphics.clone(),
+    };
+    let compute = match families.compute {
+        Some(_) => qiter.next().unwrap(),
+        None => graphics.clone(),
+    };
+    let queues = Queues {
+        graphics: graphics.clone(),
+        present: graphics,
+        transfer: transfer,
+        compute: compute,
+    };
+    (device, queues)
+}
+
+fn is_device_suitable<'a>(glfw: &Glfw, device: PhysicalDevice<'a>, req_exts: &DeviceExtensions, surface: &Arc<Surface<GlfwWindow>>) -> bool {
+    let families = find_queue_families(glfw, device);
+    let caps = query_swap_chain_support(surface, device);
+    families.is_some() && surface.is_supported(families.unwrap().graphics).unwrap() && check_device_extension_support(device, req_exts)
+        && !caps.supported_formats.is_empty() && caps.present_modes != SupportedPresentModes::none()
+}
+
+fn check_device_extension_support(device: PhysicalDevice, req_exts: &DeviceExtensions) -> bool {
+    let supported_ext = DeviceExtensions::supported_by_device(device);
+    req_exts.intersection(&supported_ext) == *req_exts
+}
+
+fn find_queue_families<'a>(glfw: &Glfw, device: PhysicalDevice<'a> ) -> Option<QueueFamilies<'a>> {
+    for family in device.queue_families() {
+        if family.supports_graphics() && vg::get_physical_device_presentation_support(glfw, &family)  {
+            return Some(QueueFamilies {
+                graphics: family,
+                transfer: find_transfer_queue_family(device),
+                compute: find_compute_queue_family(device),
+            });
+        }
+    };
+
+    None
+}
+
+/// Find a family which supports transfers but neither graphics nor compute,
+/// on many GPUs it is backed by dedicated copy engines
+pub fn find_transfer_queue_family<'a>(device: PhysicalDevice<'a>) -> Option<QueueFamily<'a>> {
+    device.queue_families()
+        .find(|family| family.supports_transfers() && !family.supports_graphics() && !family.supports_compute())
+}
+
+/// Find a family which supports compute but not graphics, its queues can run
+/// compute work asynchronously to the rendering
+pub fn find_compute_queue_family<'a>(device: PhysicalDevice<'a>) -> Option<QueueFamily<'a>> {
+    device.queue_families()
+        .find(|family| family.supports_compute() && !family.supports_graphics())
+}
@@ -0,0 +1,47 @@
+use vk_sys as vk;
+
+use vulkano::VulkanObject;
+use vulkano::instance::PhysicalDevice;
+use vulkano::instance::loader;
+use vulkano::format::Format;
+
+use std::os::raw::c_void;
+
+/// Query the features of a format with `vkGetPhysicalDeviceFormatProperties`, which vulkano does not expose
+pub fn format_properties(physical_device: PhysicalDevice, format: Format) -> vk::FormatProperties {
+    let instance = physical_device.instance().internal_object();
+    let entry_points = loader::auto_loader().unwrap();
+    let pointers = vk::InstancePointers::load(|name| {
+        entry_points.get_instance_proc_addr(instance, name.as_ptr()) as *const c_void
+    });
+
+    let mut properties = vk::FormatProperties {
+        linearTilingFeatures: 0,
+        optimalTilingFeatures: 0,
+        bufferFeatures: 0,
+    };
+    unsafe {
+        pointers.GetPhysicalDeviceFormatProperties(physical_device.internal_object(), format as vk::Format, &mut properties);
+    }
+    properties
+}
+
+/// Whether images of the format with optimal tiling support all of the features
+pub fn supports_optimal_tiling_features(physical_device: PhysicalDevice, format: Format, features: vk::FormatFeatureFlags) -> bool {
+    format_properties(physical_device, format).optimalTilingFeatures & features == features
+}
+
+/// The first of the candidates supporting all of the features with optimal tiling
+pub fn find_supported_format(physical_device: PhysicalDevice, candidates: &[Format], features: vk::FormatFeatureFlags) -> Option<Format> {
+    candidates.iter()
+        .cloned()
+        .find(|&format| supports_optimal_tiling_features(physical_device, format, features))
+}
+
+/// The most precise format the device supports for depth attachments
+pub fn find_depth_format(physical_device: PhysicalDevice) -> Format {
+    find_supported_format(physical_device,
+                          &[Format::D32Sfloat, Format::D32Sfloat_S8Uint, Format::D24Unorm_S8Uint],
+                          vk::FORMAT_FEATURE_DEPTH_STENCIL_ATTACHMENT_BIT)
+        .expect("failed to find a supported depth format")
+}
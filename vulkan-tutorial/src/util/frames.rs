@@ -0,0 +1,76 @@
+use vulkano::device::Device;
+use vulkano::sync;
+use vulkano::sync::{GpuFuture, FenceSignalFuture, FlushError};
+
+use std::sync::Arc;
+
+/// Ring of the futures of the frames the GPU may still be working on.
+///
+/// Every slot corresponds to the fence of one frame in flight. Before a slot is reused
+/// the frame that was submitted with it has to be finished, so the CPU can never be more
+/// than `max_frames_in_flight` frames ahead of the GPU.
+pub struct FramesInFlight {
+    device: Arc<Device>,
+    frames: Vec<Option<FenceSignalFuture<Box<GpuFuture>>>>,
+    current: usize,
+}
+
+impl FramesInFlight {
+    pub fn new(device: Arc<Device>, max_frames_in_flight: usize) -> FramesInFlight {
+        assert!(max_frames_in_flight > 0, "at least one frame must be in flight");
+        let mut frames = Vec::with_capacity(max_frames_in_flight);
+        for _ in 0..max_frames_in_flight {
+            frames.push(None);
+        }
+        FramesInFlight {
+            device: device,
+            frames: frames,
+            current: 0,
+        }
+    }
+
+    /// The slot of the frame between `begin_frame` and `end_frame`
+    pub fn current_frame(&self) -> usize {
+        self.current
+    }
+
+    /// Wait until the slot of the next frame is free and release the resources of all
+    /// finished frames. Returns the future the next frame starts with.
+    pub fn begin_frame(&mut self) -> Box<GpuFuture> {
+        for frame in self.frames.iter_mut() {
+            if let Some(ref mut future) = *frame {
+                future.cleanup_finished();
+            }
+        }
+
+        if let Some(future) = self.frames[self.current].take() {
+            future.wait(None).unwrap();
+        }
+
+        Box::new(sync::now(self.device.clone()))
+    }
+
+    /// Submit the future of the current frame, signalling the fence of its slot, and advance to the next slot
+    pub fn end_frame<F>(&mut self, future: F) -> Result<(), FlushError>
+        where F: GpuFuture + 'static
+    {
+        let result = match (Box::new(future) as Box<GpuFuture>).then_signal_fence_and_flush() {
+            Ok(future) => {
+                self.frames[self.current] = Some(future);
+                Ok(())
+            },
+            Err(err) => Err(err),
+        };
+        self.current = (self.current + 1) % self.frames.len();
+        result
+    }
+
+    /// Block until the GPU has finished all frames in flight
+    pub fn wait_idle(&mut self) {
+        for frame in self.frames.iter_mut() {
+            if let Some(future) = frame.take() {
+                future.wait(None).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,83 @@
+use vulkano::instance::{Instance, Features, PhysicalDevice, QueueFamily, DeviceExtensions};
+use vulkano::device::Device;
+use vulkano::format::Format;
+use vulkano::image::{AttachmentImage, ImageUsage};
+use vulkano::command_buffer::{AutoCommandBufferBuilder, CopyBufferImageError};
+
+use tutorial::Error;
+use util::device::{QueueFamilies, Queues, create_device, find_transfer_queue_family, find_compute_queue_family};
+use util::screenshot::Screenshot;
+
+use std::path::Path;
+use std::sync::Arc;
+
+/// Image rendered to instead of a swapchain image when running without a window.
+///
+/// After each frame the image is copied into a CPU-accessible buffer so the pixels can be read back.
+pub struct OffscreenTarget {
+    image: Arc<AttachmentImage>,
+    readback: Screenshot,
+}
+
+impl OffscreenTarget {
+    pub fn new(device: &Arc<Device>, dimensions: [u32; 2], format: Format) -> OffscreenTarget {
+        let usage = ImageUsage {
+            color_attachment: true,
+            transfer_source: true,
+            .. ImageUsage::none()
+        };
+        let image = AttachmentImage::with_usage(device.clone(), dimensions, format, usage).unwrap();
+
+        OffscreenTarget {
+            image: image,
+            readback: Screenshot::new(device, dimensions, format),
+        }
+    }
+
+    pub fn format(&self) -> Format {
+        self.readback.format()
+    }
+
+    pub fn dimensions(&self) -> [u32; 2] {
+        self.readback.dimensions()
+    }
+
+    pub fn image(&self) -> &Arc<AttachmentImage> {
+        &self.image
+    }
+
+    /// Record the copy of the rendered image into the CPU-accessible buffer
+    pub fn copy_to_buffer(&self, builder: AutoCommandBufferBuilder) -> Result<AutoCommandBufferBuilder, CopyBufferImageError> {
+        self.readback.copy_from_image(builder, self.image.clone())
+    }
+
+    /// Write the pixels of the last copy to a PNG file
+    pub fn save_png(&self, path: &Path) -> Result<(), Error> {
+        self.readback.save(path)
+    }
+}
+
+/// Pick the first device that can render, no presentation support is needed
+pub fn pick_physical_device<'a>(instance: &'a Arc<Instance>) -> Option<PhysicalDevice<'a>> {
+    for device in PhysicalDevice::enumerate(instance) {
+        if find_graphics_queue_family(device).is_some() {
+            println!("Using device: {}", device.name());
+            return Some(device);
+        }
+    }
+    None
+}
+
+pub fn find_graphics_queue_family<'a>(device: PhysicalDevice<'a>) -> Option<QueueFamily<'a>> {
+    device.queue_families().find(|family| family.supports_graphics())
+}
+
+/// Create the device without any extensions, the present queue is the graphics queue
+pub fn create_logical_device<'a>(phys: PhysicalDevice<'a>, features: &Features) -> (Arc<Device>, Queues) {
+    let families = QueueFamilies {
+        graphics: find_graphics_queue_family(phys).unwrap(),
+        transfer: find_transfer_queue_family(phys),
+        compute: find_compute_queue_family(phys),
+    };
+    create_device(phys, &families, &DeviceExtensions::none(), features)
+}
@@ -1,5 +1,25 @@
 use vulkano::instance::{Instance, InstanceExtensions, PhysicalDevice};
 
+use tutorial::{Tutorial, Config, Error};
+
+/// Prints the physical devices and their queue families
+pub struct ShowDevices;
+
+impl Tutorial for ShowDevices {
+    fn name(&self) -> &'static str {
+        "devices"
+    }
+
+    fn description(&self) -> &'static str {
+        "List physical devices"
+    }
+
+    fn run(&self, _config: &Config) -> Result<(), Error> {
+        show_physical_devices();
+        Ok(())
+    }
+}
+
 pub fn show_physical_devices() {
 
     let instance = Instance::new(None, &InstanceExtensions::none(), None).unwrap();
@@ -7,6 +27,8 @@ pub fn show_physical_devices() {
     for device in PhysicalDevice::enumerate(&instance) {
         println!("Name: {}", device.name());
         println!("Type: {:?}", device.ty());
+        let driver = device.driver_version();
+        println!("Driver version: {}.{}.{}", driver >> 22, (driver >> 12) & 0x3ff, driver & 0xfff);
         println!("Features: {:?}", device.supported_features());
 
         for queue_famliy in device.queue_families() {
@@ -16,4 +38,4 @@ pub fn show_physical_devices() {
 
 
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,58 @@
+use vulkano::instance;
+use vulkano::instance::{InstanceExtensions, ApplicationInfo, Version, Instance};
+use vulkano::instance::debug::{DebugCallback, Message};
+
+use std::sync::Arc;
+use std::borrow::Cow;
+
+const VALIDATION_LAYERS: &[&str; 1] = &["VK_LAYER_LUNARG_standard_validation"];
+const ENABLE_VALIDATION_LAYERS: bool = cfg!(debug_assertions);
+
+fn debug_callback(msg: &Message) {
+    println!("validation layer {}", msg.description)
+}
+
+pub fn setup_debug_callback(instance: &Arc<Instance>) -> Option<DebugCallback> {
+    if ENABLE_VALIDATION_LAYERS {
+        DebugCallback::errors_and_warnings(instance,debug_callback).ok()
+    }
+    else {
+        None
+    }
+}
+
+pub fn create_instance(application_name: &str, required_extensions: InstanceExtensions) -> Arc<Instance> {
+    if ENABLE_VALIDATION_LAYERS && !check_validation_layer_support() {
+        panic!("validation layers requested, but not available!");
+    }
+
+    // initVulkan stuff is here
+    let mut app_info = ApplicationInfo::default();
+    app_info.application_name = Some(Cow::Borrowed(application_name));
+    app_info.application_version = Some(Version { major: 1, minor: 0, patch: 0 });
+    app_info.engine_name = Some(Cow::Borrowed("No Engine"));
+    app_info.engine_version = Some(Version { major: 1, minor: 0, patch: 0 });
+
+    let extensions = InstanceExtensions {
+        ext_debug_report: ENABLE_VALIDATION_LAYERS,
+        .. required_extensions
+    };
+    let layers: &[&str] = if ENABLE_VALIDATION_LAYERS { VALIDATION_LAYERS } else { &[] };
+
+    Instance::new(Some(&app_info), &extensions, layers).unwrap()
+}
+
+fn check_validation_layer_support() -> bool {
+    for layer_name in VALIDATION_LAYERS {
+        let mut layer_found = false;
+        for layer in instance::layers_list().unwrap() {
+            if layer.name() == *layer_name {
+                layer_found = true;
+            }
+        }
+        if !layer_found {
+            return false;
+        }
+    }
+    true
+}
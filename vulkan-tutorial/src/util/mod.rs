@@ -1 +1,10 @@
-pub mod info;
\ No newline at end of file
+pub mod info;
+pub mod frames;
+pub mod headless;
+pub mod screenshot;
+pub mod instance;
+pub mod device;
+pub mod swapchain;
+pub mod render_pass;
+pub mod format;
+pub mod app;
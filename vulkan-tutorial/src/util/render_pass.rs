@@ -0,0 +1,189 @@
+use vulkano::device::{Device, DeviceOwned};
+use vulkano::format::{Format, FormatTy, ClearValue};
+use vulkano::image::{AttachmentImage, ImageLayout, ImageViewAccess};
+use vulkano::framebuffer::{RenderPass, RenderPassDescClearValues, LoadOp, StoreOp, RenderPassDesc, Framebuffer,
+    FramebufferAbstract, LayoutAttachmentDescription, LayoutPassDescription, LayoutPassDependencyDescription};
+
+use std::sync::Arc;
+
+/// Render pass with one color attachment and an optional depth attachment.
+///
+/// The first attachment is always the image that is presented or saved. With more than one
+/// sample the scene is drawn into a multisampled color attachment, which is resolved into
+/// the first attachment at the end of the render pass.
+#[derive(Debug, Clone)]
+pub struct CustomRenderPassDesc {
+    format: Format,
+    final_layout: ImageLayout,
+    depth_format: Option<Format>,
+    samples: u32,
+}
+
+impl CustomRenderPassDesc {
+    /// Clear the color attachment to black and the depth attachment to the far plane
+    pub fn clear_values(&self) -> Vec<ClearValue> {
+        let black: ClearValue = [0.0, 0.0, 0.0, 1.0].into();
+        let mut values = vec![if self.is_multisampled() { ClearValue::None } else { black }];
+        if let Some(depth_format) = self.depth_format {
+            values.push(match depth_format.ty() {
+                FormatTy::DepthStencil => ClearValue::DepthStencil((1.0, 0)),
+                _ => ClearValue::Depth(1.0),
+            });
+        }
+        if self.is_multisampled() {
+            values.push(black);
+        }
+        values
+    }
+
+    pub fn depth_format(&self) -> Option<Format> {
+        self.depth_format
+    }
+
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    fn is_multisampled(&self) -> bool {
+        self.samples > 1
+    }
+
+    fn depth_index(&self) -> Option<usize> {
+        self.depth_format.map(|_| 1)
+    }
+
+    /// The attachment the subpass draws into
+    fn color_index(&self) -> usize {
+        if self.is_multisampled() {
+            self.num_attachments() - 1
+        }
+        else {
+            0
+        }
+    }
+}
+
+unsafe impl RenderPassDescClearValues<Vec<ClearValue>> for CustomRenderPassDesc {
+    fn convert_clear_values(&self, values: Vec<ClearValue>) -> Box<Iterator<Item = ClearValue>> {
+        Box::new(values.into_iter())
+    }
+}
+
+unsafe impl RenderPassDesc for CustomRenderPassDesc {
+    fn num_attachments(&self) -> usize {
+        1 + self.depth_index().map_or(0, |_| 1) + if self.is_multisampled() { 1 } else { 0 }
+    }
+
+    fn attachment_desc(&self, num: usize) -> Option<LayoutAttachmentDescription> {
+        if num >= self.num_attachments() {
+            return None;
+        }
+
+        if Some(num) == self.depth_index() {
+            // the depth values are not needed after the frame was drawn
+            return Some(LayoutAttachmentDescription {
+                format: self.depth_format.unwrap(),
+                samples: self.samples,
+                load: LoadOp::Clear,
+                store: StoreOp::DontCare,
+                stencil_load: LoadOp::DontCare,
+                stencil_store: StoreOp::DontCare,
+                initial_layout: ImageLayout::Undefined,
+                final_layout: ImageLayout::DepthStencilAttachmentOptimal,
+            });
+        }
+
+        if num == 0 {
+            // a resolve attachment is completely overwritten, so it does not have to be cleared
+            Some(LayoutAttachmentDescription {
+                format: self.format,
+                samples: 1,
+                load: if self.is_multisampled() { LoadOp::DontCare } else { LoadOp::Clear },
+                store: StoreOp::Store,
+                stencil_load: LoadOp::DontCare,
+                stencil_store: StoreOp::DontCare,
+                initial_layout: ImageLayout::Undefined,
+                final_layout: self.final_layout,
+            })
+        }
+        else {
+            // the multisampled image is only needed until it was resolved
+            Some(LayoutAttachmentDescription {
+                format: self.format,
+                samples: self.samples,
+                load: LoadOp::Clear,
+                store: StoreOp::DontCare,
+                stencil_load: LoadOp::DontCare,
+                stencil_store: StoreOp::DontCare,
+                initial_layout: ImageLayout::Undefined,
+                final_layout: ImageLayout::ColorAttachmentOptimal,
+            })
+        }
+    }
+
+    fn num_subpasses(&self) -> usize {
+        1
+    }
+
+    fn subpass_desc(&self, _num: usize) -> Option<LayoutPassDescription> {
+        let resolve_attachments = if self.is_multisampled() {
+            vec![(0, ImageLayout::ColorAttachmentOptimal)]
+        }
+        else {
+            vec![]
+        };
+
+        Some(LayoutPassDescription {
+            color_attachments: vec![(self.color_index(), ImageLayout::ColorAttachmentOptimal)],
+            depth_stencil: self.depth_index().map(|index| (index, ImageLayout::DepthStencilAttachmentOptimal)),
+            input_attachments: vec![],
+            resolve_attachments: resolve_attachments,
+            preserve_attachments: vec![],
+        })
+    }
+
+    fn num_dependencies(&self) -> usize {
+        0
+    }
+
+    fn dependency_desc(&self, _num: usize) -> Option<LayoutPassDependencyDescription> {
+        None
+    }
+}
+
+pub fn create_render_pass(device: &Arc<Device>, format: Format, final_layout: ImageLayout,
+        depth_format: Option<Format>, samples: u32) -> Arc<RenderPass<CustomRenderPassDesc>> {
+    let rpd = CustomRenderPassDesc {
+        format: format,
+        final_layout: final_layout,
+        depth_format: depth_format,
+        samples: samples,
+    };
+    Arc::new(rpd.build_render_pass(device.clone()).unwrap())
+}
+
+/// Framebuffer rendering into `image`, the depth and multisampled color images are created
+/// if the render pass needs them
+pub fn create_framebuffer<I>(render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, image: I, dimensions: [u32; 2])
+        -> Arc<FramebufferAbstract + Send + Sync>
+    where I: ImageViewAccess + Send + Sync + 'static
+{
+    let desc = render_pass.desc();
+    let device = render_pass.device();
+    let depth = desc.depth_format
+        .map(|format| AttachmentImage::transient_multisampled(device.clone(), dimensions, desc.samples, format).unwrap());
+    let color = if desc.is_multisampled() {
+        Some(AttachmentImage::transient_multisampled(device.clone(), dimensions, desc.samples, desc.format).unwrap())
+    }
+    else {
+        None
+    };
+
+    let builder = Framebuffer::start(render_pass.clone()).add(image).unwrap();
+    match (depth, color) {
+        (None, None) => Arc::new(builder.build().unwrap()),
+        (Some(depth), None) => Arc::new(builder.add(depth).unwrap().build().unwrap()),
+        (None, Some(color)) => Arc::new(builder.add(color).unwrap().build().unwrap()),
+        (Some(depth), Some(color)) => Arc::new(builder.add(depth).unwrap().add(color).unwrap().build().unwrap()),
+    }
+}
@@ -0,0 +1,94 @@
+use vulkano::device::Device;
+use vulkano::format::Format;
+use vulkano::image::ImageAccess;
+use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
+use vulkano::buffer::cpu_access::ReadLockError;
+use vulkano::command_buffer::{AutoCommandBufferBuilder, CopyBufferImageError};
+
+use image;
+
+use tutorial::Error;
+
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+
+/// CPU-accessible copy of a rendered image which can be saved as a PNG file
+pub struct Screenshot {
+    format: Format,
+    dimensions: [u32; 2],
+    buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+}
+
+impl Screenshot {
+    pub fn new(device: &Arc<Device>, dimensions: [u32; 2], format: Format) -> Screenshot {
+        let size = dimensions[0] as usize * dimensions[1] as usize * format.size().unwrap();
+        let usage = BufferUsage {
+            transfer_destination: true,
+            .. BufferUsage::none()
+        };
+        let buffer = CpuAccessibleBuffer::from_iter(device.clone(), usage, (0 .. size).map(|_| 0u8)).unwrap();
+
+        Screenshot {
+            format: format,
+            dimensions: dimensions,
+            buffer: buffer,
+        }
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn dimensions(&self) -> [u32; 2] {
+        self.dimensions
+    }
+
+    /// Record the copy of the image into the CPU-accessible buffer.
+    /// The image must have been created with the `transfer_source` usage.
+    pub fn copy_from_image<I>(&self, builder: AutoCommandBufferBuilder, image: I) -> Result<AutoCommandBufferBuilder, CopyBufferImageError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        builder.copy_image_to_buffer(image, self.buffer.clone())
+    }
+
+    /// The pixels of the last copy, tightly packed in the format of the image
+    pub fn read_pixels(&self) -> Result<Vec<u8>, ReadLockError> {
+        Ok(self.buffer.read()?.to_vec())
+    }
+
+    /// Write the pixels of the last copy to a PNG file.
+    /// The copy must have finished executing on the GPU.
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        save_png(path, self.dimensions, self.format, &self.read_pixels()?)?;
+        Ok(())
+    }
+}
+
+/// Convert tightly packed pixels to 8 bit RGBA, returns `None` for unsupported formats
+pub fn to_rgba(format: Format, pixels: &[u8]) -> Option<Vec<u8>> {
+    match format {
+        Format::R8G8B8A8Unorm | Format::R8G8B8A8Srgb |
+        Format::A8B8G8R8UnormPack32 | Format::A8B8G8R8SrgbPack32 => Some(pixels.to_vec()),
+        Format::B8G8R8A8Unorm | Format::B8G8R8A8Srgb => {
+            Some(pixels.chunks(4).flat_map(|p| vec![p[2], p[1], p[0], p[3]]).collect())
+        },
+        Format::R8G8B8Unorm | Format::R8G8B8Srgb => {
+            Some(pixels.chunks(3).flat_map(|p| vec![p[0], p[1], p[2], 255]).collect())
+        },
+        Format::B8G8R8Unorm | Format::B8G8R8Srgb => {
+            Some(pixels.chunks(3).flat_map(|p| vec![p[2], p[1], p[0], 255]).collect())
+        },
+        _ => None,
+    }
+}
+
+/// Write tightly packed pixels of the given format to a PNG file
+pub fn save_png(path: &Path, dimensions: [u32; 2], format: Format, pixels: &[u8]) -> io::Result<()> {
+    let rgba = match to_rgba(format, pixels) {
+        Some(rgba) => rgba,
+        None => return Err(io::Error::new(io::ErrorKind::InvalidInput,
+                                          format!("cannot convert {:?} to RGBA", format))),
+    };
+    image::save_buffer(path, &rgba, dimensions[0], dimensions[1], image::ColorType::RGBA(8))
+}
@@ -0,0 +1,120 @@
+use glfw::Glfw;
+
+use vulkano::instance::{Instance, PhysicalDevice};
+use vulkano::device::{Device, Queue};
+use vulkano::swapchain::{Surface, Capabilities, ColorSpace, PresentMode, Swapchain, CompositeAlpha, SwapchainCreationError};
+use vulkano::format::Format;
+use vulkano::image::{ImageUsage, SwapchainImage};
+use vulkano::sync::SharingMode;
+use vulkano::framebuffer::{FramebufferAbstract, RenderPass};
+
+use vulkano_glfw as vg;
+use vulkano_glfw::{GlfwWindow, GlfwWindowBuilder};
+
+use util::render_pass::{CustomRenderPassDesc, create_framebuffer};
+
+use std::sync::Arc;
+use std::cmp::{max, min};
+
+pub fn init_window(width: u32, height: u32) -> (Glfw, GlfwWindow) {
+    let mut glfw = vg::init().unwrap();
+    let window = GlfwWindowBuilder::new(width, height, "Vulkan")
+        .build(&mut glfw).unwrap();
+    (glfw,window)
+}
+
+pub fn create_surface(instance: &Arc<Instance>, window: GlfwWindow ) -> Arc<Surface<GlfwWindow>> {
+    vg::create_window_surface(instance.clone(), window).unwrap()
+}
+
+pub fn query_swap_chain_support(surface: &Arc<Surface<GlfwWindow>>, device: PhysicalDevice) -> Capabilities {
+    surface.capabilities(device).unwrap()
+}
+
+pub fn create_swap_chain(device: &Arc<Device>, surface: &Arc<Surface<GlfwWindow>>, queue: &Arc<Queue>, old_swapchain: Option<&Arc<Swapchain<GlfwWindow>>>)
+        -> Result<(Arc<Swapchain<GlfwWindow>>, Vec<Arc<SwapchainImage<GlfwWindow>>>), SwapchainCreationError> {
+    let caps = query_swap_chain_support(&surface, device.physical_device());
+
+    let req_image_count = caps.min_image_count + 1;
+    let image_count = match caps.max_image_count {
+        Some(max_image) => if req_image_count > max_image {
+            max_image
+        }
+        else {
+            req_image_count
+        }
+        None => req_image_count,
+    };
+
+    let (format, _color_space) = choose_swap_surface_format(&caps);
+    let extend = choose_swap_extend(&caps, surface.window().framebuffer_extent());
+
+    Swapchain::new(device.clone(),
+                        surface.clone(),
+                        image_count,
+                        format,
+                        extend,
+                        1, // layers
+                        ImageUsage {
+                            color_attachment: true,
+                            // needed to copy the images for screenshots
+                            transfer_source: caps.supported_usage_flags.transfer_source,
+                            .. ImageUsage::none()
+                        },
+                        SharingMode::from(queue),
+                        caps.current_transform,
+                        CompositeAlpha::Opaque,
+                        choose_swap_present_mode(&caps),
+                        true, // clipped
+                        old_swapchain
+                        )
+}
+
+fn choose_swap_surface_format(caps: &Capabilities) -> (Format, ColorSpace) {
+    let avail_formats = &caps.supported_formats;
+    if avail_formats.len() == 0 {
+        (Format::B8G8R8Unorm, ColorSpace::SrgbNonLinear)
+    }
+    else {
+        if avail_formats.contains(&(Format::B8G8R8Unorm, ColorSpace::SrgbNonLinear)) {
+            (Format::B8G8R8Unorm, ColorSpace::SrgbNonLinear)
+        }
+        else {
+            avail_formats[0]
+        }
+    }
+}
+
+fn choose_swap_present_mode(caps: &Capabilities) -> PresentMode {
+    let avail_modes = caps.present_modes;
+    if avail_modes.mailbox {
+        PresentMode::Mailbox
+    }
+    else {
+        if avail_modes.immediate {
+            PresentMode::Immediate
+        }
+        else {
+            PresentMode::Fifo
+        }
+    }
+}
+
+fn choose_swap_extend(caps: &Capabilities, framebuffer_extent: [u32;2]) -> [u32;2] {
+    match caps.current_extent {
+        Some(e) => e,
+        None => {
+            let width = max(caps.min_image_extent[0], min(caps.max_image_extent[0], framebuffer_extent[0]));
+            let height = max(caps.min_image_extent[1], min(caps.max_image_extent[1], framebuffer_extent[1]));
+            [width, height]
+        }
+    }
+}
+
+/// One framebuffer per swapchain image, each with its own depth and multisampled color image if the render pass has them
+pub fn create_framebuffers(render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, images: &Vec<Arc<SwapchainImage<GlfwWindow>>>)
+        -> Vec<Arc<FramebufferAbstract + Send + Sync>> {
+    images.iter()
+        .map(|image| create_framebuffer(render_pass, image.clone(), image.dimensions()))
+        .collect()
+}
@@ -0,0 +1,104 @@
+use vulkano::device::{Device, Queue};
+use vulkano::buffer::{BufferUsage, ImmutableBuffer};
+use vulkano::framebuffer::RenderPass;
+use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
+use vulkano::descriptor::descriptor_set::DescriptorSetsCollection;
+use vulkano::pipeline::GraphicsPipelineAbstract;
+use vulkano::pipeline::vertex::VertexSource;
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use util::app::{Scene, Frame};
+use util::device::Queues;
+use util::render_pass::CustomRenderPassDesc;
+use vertex::vertex_buffers::{Vertex, VertexPipeline, create_graphics_pipeline};
+use vertex::staging_buffer::upload_buffer;
+
+use std::sync::Arc;
+use std::u16;
+
+pub const VERTICES: [Vertex; 4] = [
+    Vertex { position: [-0.5, -0.5], color: [1.0, 0.0, 0.0] },
+    Vertex { position: [0.5, -0.5], color: [0.0, 1.0, 0.0] },
+    Vertex { position: [0.5, 0.5], color: [0.0, 0.0, 1.0] },
+    Vertex { position: [-0.5, 0.5], color: [1.0, 1.0, 1.0] },
+];
+
+/// Two triangles sharing the vertices of the diagonal
+pub const INDICES: [u32; 6] = [0, 1, 2, 2, 3, 0];
+
+pub struct IndexBuffer;
+
+impl Tutorial for IndexBuffer {
+    fn name(&self) -> &'static str {
+        "index_buffer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Index buffer"
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        app::run("Index Buffer", config, IndexBufferScene::new)
+    }
+}
+
+/// Index buffer with the smallest index type that can address all vertices
+pub enum Indices {
+    U16(Arc<ImmutableBuffer<[u16]>>),
+    U32(Arc<ImmutableBuffer<[u32]>>),
+}
+
+impl Indices {
+    pub fn new(device: &Arc<Device>, transfer_queue: &Arc<Queue>, indices: &[u32]) -> Indices {
+        let usage = BufferUsage::index_buffer();
+        if indices.iter().all(|&index| index <= u16::MAX as u32) {
+            Indices::U16(upload_buffer(device, transfer_queue, indices.iter().map(|&index| index as u16), usage))
+        }
+        else {
+            Indices::U32(upload_buffer(device, transfer_queue, indices.iter().cloned(), usage))
+        }
+    }
+
+    /// Record an indexed draw of the vertices with the index type of the buffer
+    pub fn draw<Gp, V, S>(&self, builder: AutoCommandBufferBuilder, pipeline: Gp, vertices: V, sets: S) -> AutoCommandBufferBuilder
+        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone,
+              S: DescriptorSetsCollection
+    {
+        match *self {
+            Indices::U16(ref indices) => builder.draw_indexed(pipeline, DynamicState::none(),
+                vertices, indices.clone(), sets, ()).unwrap(),
+            Indices::U32(ref indices) => builder.draw_indexed(pipeline, DynamicState::none(),
+                vertices, indices.clone(), sets, ()).unwrap(),
+        }
+    }
+}
+
+/// Draws a rectangle from four vertices, the index buffer references the shared vertices twice
+struct IndexBufferScene {
+    device: Arc<Device>,
+    vertex_buffer: Arc<ImmutableBuffer<[Vertex]>>,
+    indices: Indices,
+    pipeline: Option<Arc<VertexPipeline>>,
+}
+
+impl IndexBufferScene {
+    fn new(device: &Arc<Device>, queues: &Queues) -> IndexBufferScene {
+        IndexBufferScene {
+            device: device.clone(),
+            vertex_buffer: upload_buffer(device, &queues.transfer, VERTICES.iter().cloned(), BufferUsage::vertex_buffer()),
+            indices: Indices::new(device, &queues.transfer, &INDICES),
+            pipeline: None,
+        }
+    }
+}
+
+impl Scene for IndexBufferScene {
+    fn create_pipeline(&mut self, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, dimensions: [u32; 2]) {
+        self.pipeline = Some(create_graphics_pipeline(&self.device, dimensions, render_pass));
+    }
+
+    fn draw(&mut self, builder: AutoCommandBufferBuilder, _frame: &Frame) -> AutoCommandBufferBuilder {
+        self.indices.draw(builder, self.pipeline.clone().unwrap(), self.vertex_buffer.clone(), ())
+    }
+}
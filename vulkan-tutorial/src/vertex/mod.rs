@@ -0,0 +1,3 @@
+pub mod vertex_buffers;
+pub mod staging_buffer;
+pub mod index_buffer;
@@ -0,0 +1,76 @@
+use vulkano::device::{Device, Queue};
+use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer, ImmutableBuffer};
+use vulkano::framebuffer::RenderPass;
+use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
+use vulkano::sync::GpuFuture;
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use util::app::{Scene, Frame};
+use util::device::Queues;
+use util::render_pass::CustomRenderPassDesc;
+use vertex::vertex_buffers::{Vertex, VertexPipeline, VERTICES, create_graphics_pipeline};
+
+use std::sync::Arc;
+
+pub struct StagingBuffer;
+
+impl Tutorial for StagingBuffer {
+    fn name(&self) -> &'static str {
+        "staging_buffer"
+    }
+
+    fn description(&self) -> &'static str {
+        "Staging buffer"
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        app::run("Staging Buffer", config, StagingBufferScene::new)
+    }
+}
+
+/// Draws the triangle from vertices in device local memory, which the CPU cannot write to
+struct StagingBufferScene {
+    device: Arc<Device>,
+    vertex_buffer: Arc<ImmutableBuffer<[Vertex]>>,
+    pipeline: Option<Arc<VertexPipeline>>,
+}
+
+impl StagingBufferScene {
+    fn new(device: &Arc<Device>, queues: &Queues) -> StagingBufferScene {
+        StagingBufferScene {
+            device: device.clone(),
+            vertex_buffer: upload_buffer(device, &queues.transfer, VERTICES.iter().cloned(), BufferUsage::vertex_buffer()),
+            pipeline: None,
+        }
+    }
+}
+
+impl Scene for StagingBufferScene {
+    fn create_pipeline(&mut self, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, dimensions: [u32; 2]) {
+        self.pipeline = Some(create_graphics_pipeline(&self.device, dimensions, render_pass));
+    }
+
+    fn draw(&mut self, builder: AutoCommandBufferBuilder, _frame: &Frame) -> AutoCommandBufferBuilder {
+        builder.draw(self.pipeline.clone().unwrap(),
+            DynamicState::none(),
+            self.vertex_buffer.clone(), (), ()).unwrap()
+    }
+}
+
+/// Write the data into a host visible staging buffer and copy it into a device local buffer.
+/// The copy is submitted to the transfer queue, which is a dedicated transfer queue if the device has one.
+pub fn upload_buffer<T, I>(device: &Arc<Device>, transfer_queue: &Arc<Queue>, data: I, usage: BufferUsage) -> Arc<ImmutableBuffer<[T]>>
+    where T: Send + Sync + 'static,
+          I: ExactSizeIterator<Item = T>
+{
+    let staging_buffer = CpuAccessibleBuffer::from_iter(device.clone(), BufferUsage::transfer_source(), data).unwrap();
+    let (buffer, upload) = ImmutableBuffer::from_buffer(staging_buffer, usage, transfer_queue.clone()).unwrap();
+
+    // the buffer is shared by all queue families, so it can be used by the graphics queue
+    // as soon as the copy has finished
+    upload.then_signal_fence_and_flush().unwrap()
+        .wait(None).unwrap();
+
+    buffer
+}
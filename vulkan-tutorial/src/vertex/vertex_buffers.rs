@@ -0,0 +1,126 @@
+use vulkano::device::Device;
+use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
+use vulkano::framebuffer::{Subpass, RenderPass};
+use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
+use vulkano::descriptor::PipelineLayoutAbstract;
+use vulkano::pipeline::GraphicsPipeline;
+use vulkano::pipeline::vertex::SingleBufferDefinition;
+use vulkano::pipeline::viewport::{Viewport, Scissor};
+
+use tutorial::{Tutorial, Config, Error};
+use util::app;
+use util::app::{Scene, Frame};
+use util::device::Queues;
+use util::render_pass::CustomRenderPassDesc;
+
+use std::sync::Arc;
+
+/// Vertex with the attributes of the vertex shader, the member names must match the shader inputs
+#[derive(Debug, Clone, Copy)]
+pub struct Vertex {
+    pub position: [f32; 2],
+    pub color: [f32; 3],
+}
+
+impl_vertex!(Vertex, position, color);
+
+pub const VERTICES: [Vertex; 3] = [
+    Vertex { position: [0.0, -0.5], color: [1.0, 0.0, 0.0] },
+    Vertex { position: [0.5, 0.5], color: [0.0, 1.0, 0.0] },
+    Vertex { position: [-0.5, 0.5], color: [0.0, 0.0, 1.0] },
+];
+
+pub type VertexPipeline = GraphicsPipeline<SingleBufferDefinition<Vertex>, Box<PipelineLayoutAbstract + Send + Sync>, Arc<RenderPass<CustomRenderPassDesc>>>;
+
+#[allow(unused)]
+pub mod vs {
+    #[derive(VulkanoShader)]
+    #[ty = "vertex"]
+    #[path = "src/vertex/shader.vert"]
+    struct Dummy;
+}
+
+#[allow(unused)]
+pub mod fs {
+    #[derive(VulkanoShader)]
+    #[ty = "fragment"]
+    #[path = "src/vertex/shader.frag"]
+    struct Dummy;
+}
+
+pub struct VertexBuffers;
+
+impl Tutorial for VertexBuffers {
+    fn name(&self) -> &'static str {
+        "vertex_buffers"
+    }
+
+    fn description(&self) -> &'static str {
+        "Vertex buffers"
+    }
+
+    fn run(&self, config: &Config) -> Result<(), Error> {
+        app::run("Vertex Buffers", config, VertexBufferScene::new)
+    }
+}
+
+/// Draws the triangle from the vertices in a buffer the CPU writes to directly
+struct VertexBufferScene {
+    device: Arc<Device>,
+    vertex_buffer: Arc<CpuAccessibleBuffer<[Vertex]>>,
+    pipeline: Option<Arc<VertexPipeline>>,
+}
+
+impl VertexBufferScene {
+    fn new(device: &Arc<Device>, _queues: &Queues) -> VertexBufferScene {
+        VertexBufferScene {
+            device: device.clone(),
+            vertex_buffer: create_vertex_buffer(device),
+            pipeline: None,
+        }
+    }
+}
+
+impl Scene for VertexBufferScene {
+    fn create_pipeline(&mut self, render_pass: &Arc<RenderPass<CustomRenderPassDesc>>, dimensions: [u32; 2]) {
+        self.pipeline = Some(create_graphics_pipeline(&self.device, dimensions, render_pass));
+    }
+
+    fn draw(&mut self, builder: AutoCommandBufferBuilder, _frame: &Frame) -> AutoCommandBufferBuilder {
+        builder.draw(self.pipeline.clone().unwrap(),
+            DynamicState::none(),
+            self.vertex_buffer.clone(), (), ()).unwrap()
+    }
+}
+
+fn create_vertex_buffer(device: &Arc<Device>) -> Arc<CpuAccessibleBuffer<[Vertex]>> {
+    CpuAccessibleBuffer::from_iter(device.clone(), BufferUsage::vertex_buffer(), VERTICES.iter().cloned()).unwrap()
+}
+
+/// Same pipeline as for the hello triangle, but the vertices are read from a buffer with `Vertex` layout
+pub fn create_graphics_pipeline(device: &Arc<Device>, dimensions: [u32; 2],
+        render_pass: &Arc<RenderPass<CustomRenderPassDesc>>) -> Arc<VertexPipeline> {
+    let vs = vs::Shader::load(device.clone()).expect("failed to create shader module");
+    let fs = fs::Shader::load(device.clone()).expect("failed to create shader module");
+
+    let viewport = Viewport {
+        origin: [0.0, 0.0],
+        dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+        depth_range: 0.0 .. 1.0,
+    };
+
+    let scissor = Scissor {
+        origin: [0,0],
+        dimensions: dimensions,
+    };
+
+    Arc::new(GraphicsPipeline::start()
+        .vertex_input_single_buffer::<Vertex>()
+        .vertex_shader(vs.main_entry_point(), ())
+        .triangle_list()
+        .viewports_scissors(Some((viewport, scissor)))
+        .fragment_shader(fs.main_entry_point(), ())
+        .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+        .build(device.clone())
+        .unwrap())
+}
@@ -0,0 +1,240 @@
+//! Golden image regression tests for the tutorials.
+//!
+//! Every tutorial is rendered headless for a fixed number of frames and the screenshot of
+//! the last frame is compared against the reference image `tests/golden/<tutorial>.png`.
+//! For mismatching tutorials the actual image and a diff image are written to the `golden`
+//! directory next to the test executable.
+//!
+//! Rendering needs a Vulkan driver, e.g. lavapipe on machines without GPU, so the test is
+//! ignored by default and must be run with `cargo test -p vulkan-tutorial -- --ignored`.
+//! A tutorial without reference image fails the test before anything is rendered. Set
+//! `UPDATE_GOLDEN=1` to record the current output as new reference images, e.g. for a new
+//! tutorial, and commit them to `tests/golden`.
+//!
+//! The reference images are recorded with lavapipe. Recording also writes the name and
+//! driver version of the devices to `tests/golden/device.txt`, commit it together with the
+//! images so that mismatches caused by a different driver can be told apart from regressions.
+
+extern crate image;
+
+use image::{Rgba, RgbaImage};
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Number of frames rendered before the screenshot is taken
+const FRAMES: u32 = 3;
+
+/// Maximum difference per color channel for a pixel to be considered equal
+const TOLERANCE: u8 = 2;
+
+/// Fraction of pixels allowed to differ, e.g. because of different rasterization of edges
+const MAX_MISMATCH_RATIO: f64 = 0.001;
+
+struct Comparison {
+    mismatched: usize,
+    total: usize,
+    diff: RgbaImage,
+}
+
+impl Comparison {
+    fn mismatch_ratio(&self) -> f64 {
+        self.mismatched as f64 / self.total as f64
+    }
+}
+
+/// Compare two images pixel by pixel. The diff image shows mismatching pixels in red
+/// on top of a dimmed grayscale version of the actual image.
+fn compare(expected: &RgbaImage, actual: &RgbaImage, tolerance: u8) -> Result<Comparison, String> {
+    if expected.dimensions() != actual.dimensions() {
+        return Err(format!("expected size {:?}, got {:?}", expected.dimensions(), actual.dimensions()));
+    }
+
+    let (width, height) = actual.dimensions();
+    let mut diff = RgbaImage::new(width, height);
+    let mut mismatched = 0;
+
+    for (x, y, pixel) in actual.enumerate_pixels() {
+        let reference = expected.get_pixel(x, y);
+        let equal = pixel.data.iter().zip(reference.data.iter())
+            .all(|(a, e)| (*a as i16 - *e as i16).abs() <= tolerance as i16);
+        if equal {
+            let luma = ((pixel.data[0] as u32 + pixel.data[1] as u32 + pixel.data[2] as u32) / 9) as u8;
+            diff.put_pixel(x, y, Rgba([luma, luma, luma, 255]));
+        }
+        else {
+            mismatched += 1;
+            diff.put_pixel(x, y, Rgba([255, 0, 0, 255]));
+        }
+    }
+
+    Ok(Comparison {
+        mismatched: mismatched,
+        total: (width * height) as usize,
+        diff: diff,
+    })
+}
+
+/// The directory containing the test executable, the tutorial binary lives in its parent
+fn target_dir() -> PathBuf {
+    let mut dir = env::current_exe().unwrap();
+    dir.pop();
+    if dir.ends_with("deps") {
+        dir.pop();
+    }
+    dir
+}
+
+fn tutorial_binary() -> PathBuf {
+    target_dir().join(format!("vulkan-tutorial{}", env::consts::EXE_SUFFIX))
+}
+
+fn reference_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+fn reference_path(name: &str) -> PathBuf {
+    reference_dir().join(format!("{}.png", name))
+}
+
+fn is_recording() -> bool {
+    env::var("UPDATE_GOLDEN").is_ok()
+}
+
+fn output_dir() -> PathBuf {
+    let dir = target_dir().join("golden");
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Ask the binary for the names of all tutorials
+fn tutorial_names() -> Vec<String> {
+    let output = Command::new(tutorial_binary())
+        .args(&["show", "tutorials"])
+        .output()
+        .expect("could not run vulkan-tutorial");
+    assert!(output.status.success(), "listing the tutorials failed");
+
+    String::from_utf8_lossy(&output.stdout).lines()
+        .filter_map(|line| line.split_whitespace().next())
+        .map(|name| name.to_owned())
+        .collect()
+}
+
+/// Write the devices the references are recorded with next to the reference images
+fn record_devices() {
+    let output = Command::new(tutorial_binary())
+        .args(&["show", "devices"])
+        .output()
+        .expect("could not run vulkan-tutorial");
+    assert!(output.status.success(), "listing the devices failed");
+
+    let devices: String = String::from_utf8_lossy(&output.stdout).lines()
+        .filter(|line| line.starts_with("Name:") || line.starts_with("Driver version:"))
+        .map(|line| format!("{}\n", line))
+        .collect();
+    fs::create_dir_all(reference_dir()).unwrap();
+    fs::write(reference_dir().join("device.txt"), devices).unwrap();
+}
+
+/// Render the tutorial headless and return the screenshot of the last frame
+fn render(name: &str) -> Result<RgbaImage, String> {
+    let path = output_dir().join(format!("{}.actual.png", name));
+    let _ = fs::remove_file(&path);
+
+    let output = Command::new(tutorial_binary())
+        .args(&["run", name, "--headless", "--frames", &FRAMES.to_string()])
+        .arg("--screenshot").arg(&path)
+        .output()
+        .map_err(|err| format!("could not run vulkan-tutorial: {}", err))?;
+    if !output.status.success() || !path.exists() {
+        return Err(format!("rendering failed:\n{}{}",
+                           String::from_utf8_lossy(&output.stdout), String::from_utf8_lossy(&output.stderr)));
+    }
+
+    image::open(&path).map(|image| image.to_rgba()).map_err(|err| err.to_string())
+}
+
+fn check_tutorial(name: &str) -> Result<(), String> {
+    let actual = render(name)?;
+    let reference_path = reference_path(name);
+
+    if is_recording() {
+        fs::create_dir_all(reference_dir()).unwrap();
+        return actual.save(&reference_path).map_err(|err| err.to_string());
+    }
+
+    let expected = image::open(&reference_path).map_err(|err| err.to_string())?.to_rgba();
+
+    let comparison = compare(&expected, &actual, TOLERANCE)?;
+    if comparison.mismatch_ratio() > MAX_MISMATCH_RATIO {
+        let diff_path = output_dir().join(format!("{}.diff.png", name));
+        comparison.diff.save(&diff_path).map_err(|err| err.to_string())?;
+        return Err(format!("{} of {} pixels differ, see {}",
+                           comparison.mismatched, comparison.total, diff_path.display()));
+    }
+    Ok(())
+}
+
+#[test]
+#[ignore]
+fn tutorials_match_golden_images() {
+    let names = tutorial_names();
+    assert!(!names.is_empty(), "no tutorials found");
+
+    // a missing reference is an error and not a skipped tutorial, otherwise regressions would go unnoticed
+    if !is_recording() {
+        let missing: Vec<String> = names.iter()
+            .map(|name| reference_path(name))
+            .filter(|path| !path.exists())
+            .map(|path| path.display().to_string())
+            .collect();
+        assert!(missing.is_empty(), "missing reference images, record them with UPDATE_GOLDEN=1:\n{}", missing.join("\n"));
+    }
+    else {
+        record_devices();
+    }
+
+    let failures: Vec<String> = names.iter()
+        .filter_map(|name| check_tutorial(name).err().map(|err| format!("{}: {}", name, err)))
+        .collect();
+
+    assert!(failures.is_empty(), "golden image mismatch:\n{}", failures.join("\n"));
+}
+
+#[test]
+fn compare_identical_images() {
+    let image = RgbaImage::from_pixel(4, 3, Rgba([10, 20, 30, 255]));
+    let comparison = compare(&image, &image, 0).unwrap();
+    assert_eq!(comparison.mismatched, 0);
+    assert_eq!(comparison.total, 12);
+}
+
+#[test]
+fn compare_within_tolerance() {
+    let expected = RgbaImage::from_pixel(2, 2, Rgba([100, 100, 100, 255]));
+    let actual = RgbaImage::from_pixel(2, 2, Rgba([102, 98, 100, 255]));
+    assert_eq!(compare(&expected, &actual, 2).unwrap().mismatched, 0);
+    assert_eq!(compare(&expected, &actual, 1).unwrap().mismatched, 4);
+}
+
+#[test]
+fn compare_marks_mismatches_in_diff() {
+    let expected = RgbaImage::from_pixel(2, 2, Rgba([0, 0, 0, 255]));
+    let mut actual = expected.clone();
+    actual.put_pixel(1, 0, Rgba([0, 255, 0, 255]));
+
+    let comparison = compare(&expected, &actual, 2).unwrap();
+    assert_eq!(comparison.mismatched, 1);
+    assert_eq!(comparison.diff.get_pixel(1, 0).data, [255, 0, 0, 255]);
+    assert_eq!(comparison.diff.get_pixel(0, 0).data, [0, 0, 0, 255]);
+}
+
+#[test]
+fn compare_different_sizes() {
+    let expected = RgbaImage::new(2, 2);
+    let actual = RgbaImage::new(3, 2);
+    assert!(compare(&expected, &actual, 0).is_err());
+}
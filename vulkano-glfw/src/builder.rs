@@ -0,0 +1,180 @@
+use std::os::raw::c_int;
+
+use glfw;
+use glfw::{Glfw, WindowHint, ClientApiHint, WindowMode};
+
+use {GlfwWindow, VulkanoGlfwError, WindowEventKind};
+use error::{clear_glfw_error, take_glfw_error};
+use main_thread;
+
+// Window hints introduced with GLFW 3.3 which are not exposed by the glfw crate yet
+const TRANSPARENT_FRAMEBUFFER: c_int = 0x0002000A;
+const FOCUS_ON_SHOW: c_int = 0x0002000C;
+const SCALE_TO_MONITOR: c_int = 0x0002200C;
+
+/// Builder for a `GlfwWindow` which can be used to create a Vulkan surface.
+///
+/// The client API hint is always set to `NoApi`, so no OpenGL context is created for the window.
+///
+/// The event polling has to be configured here for windows whose surface is shared, e.g. the
+/// ones of a `WindowManager`, because `set_event_polling` needs exclusive access to the window.
+#[derive(Clone, Debug)]
+pub struct GlfwWindowBuilder {
+    width: u32,
+    height: u32,
+    title: String,
+    resizable: bool,
+    decorated: bool,
+    floating: bool,
+    transparent_framebuffer: bool,
+    focus_on_show: bool,
+    scale_to_monitor: bool,
+    monitor: Option<usize>,
+    all_polling: Option<bool>,
+    event_polling: Vec<(WindowEventKind, bool)>,
+}
+
+impl GlfwWindowBuilder {
+    /// Start building a window with the given size and title and GLFW's default hints
+    pub fn new(width: u32, height: u32, title: &str) -> GlfwWindowBuilder {
+        GlfwWindowBuilder {
+            width: width,
+            height: height,
+            title: title.to_owned(),
+            resizable: true,
+            decorated: true,
+            floating: false,
+            transparent_framebuffer: false,
+            focus_on_show: true,
+            scale_to_monitor: false,
+            monitor: None,
+            all_polling: None,
+            event_polling: Vec::new(),
+        }
+    }
+
+    pub fn size(mut self, width: u32, height: u32) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_owned();
+        self
+    }
+
+    /// Whether the window can be resized by the user
+    pub fn resizable(mut self, resizable: bool) -> Self {
+        self.resizable = resizable;
+        self
+    }
+
+    /// Whether the window has decorations such as a border and a close widget
+    pub fn decorated(mut self, decorated: bool) -> Self {
+        self.decorated = decorated;
+        self
+    }
+
+    /// Whether the window floats above other regular windows
+    pub fn floating(mut self, floating: bool) -> Self {
+        self.floating = floating;
+        self
+    }
+
+    /// Whether the window framebuffer is transparent (requires GLFW 3.3)
+    pub fn transparent_framebuffer(mut self, transparent: bool) -> Self {
+        self.transparent_framebuffer = transparent;
+        self
+    }
+
+    /// Whether the window gets the input focus when it is shown (requires GLFW 3.3)
+    pub fn focus_on_show(mut self, focus: bool) -> Self {
+        self.focus_on_show = focus;
+        self
+    }
+
+    /// Whether the window is resized based on the content scale of its monitor (requires GLFW 3.3)
+    pub fn scale_to_monitor(mut self, scale: bool) -> Self {
+        self.scale_to_monitor = scale;
+        self
+    }
+
+    /// Create a full screen window on the primary monitor instead of a windowed one
+    pub fn fullscreen(mut self, fullscreen: bool) -> Self {
+        // the primary monitor is always the first one reported by GLFW
+        self.monitor = if fullscreen { Some(0) } else { None };
+        self
+    }
+
+    /// Create a full screen window on the connected monitor with the given index
+    pub fn monitor(mut self, index: usize) -> Self {
+        self.monitor = Some(index);
+        self
+    }
+
+    /// Enable or disable the delivery of one kind of event, see `GlfwWindow::set_event_polling`
+    pub fn event_polling(mut self, kind: WindowEventKind, should_poll: bool) -> Self {
+        self.event_polling.push((kind, should_poll));
+        self
+    }
+
+    /// Enable or disable the delivery of all kinds of events, replacing the earlier `event_polling` calls
+    pub fn all_polling(mut self, should_poll: bool) -> Self {
+        self.all_polling = Some(should_poll);
+        self.event_polling.clear();
+        self
+    }
+
+    /// Apply the window hints and create the window, which is only possible on the main thread
+    pub fn build(&self, glfw: &mut Glfw) -> Result<GlfwWindow, VulkanoGlfwError> {
+        main_thread::mark_main_thread();
+        main_thread::check_main_thread()?;
+        glfw.default_window_hints();
+        glfw.window_hint(WindowHint::ClientApi(ClientApiHint::NoApi));
+        glfw.window_hint(WindowHint::Resizable(self.resizable));
+        glfw.window_hint(WindowHint::Decorated(self.decorated));
+        glfw.window_hint(WindowHint::Floating(self.floating));
+        unsafe {
+            glfw::ffi::glfwWindowHint(TRANSPARENT_FRAMEBUFFER, self.transparent_framebuffer as c_int);
+            glfw::ffi::glfwWindowHint(FOCUS_ON_SHOW, self.focus_on_show as c_int);
+            glfw::ffi::glfwWindowHint(SCALE_TO_MONITOR, self.scale_to_monitor as c_int);
+        }
+
+        clear_glfw_error();
+        let window = match self.monitor {
+            None => glfw.create_window(self.width, self.height, &self.title, WindowMode::Windowed)
+                .ok_or_else(|| VulkanoGlfwError::WindowCreationFailed(take_glfw_error())),
+            Some(index) => glfw.with_connected_monitors(|glfw, monitors| {
+                match monitors.get(index) {
+                    Some(monitor) => glfw.create_window(self.width, self.height, &self.title, WindowMode::FullScreen(monitor))
+                        .ok_or_else(|| VulkanoGlfwError::WindowCreationFailed(take_glfw_error())),
+                    None => Err(VulkanoGlfwError::MonitorNotFound),
+                }
+            }),
+        };
+        let mut window = window.map(GlfwWindow::from)?;
+        if let Some(should_poll) = self.all_polling {
+            window.set_all_polling(should_poll)?;
+        }
+        for &(kind, should_poll) in &self.event_polling {
+            window.set_event_polling(kind, should_poll)?;
+        }
+        Ok(window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_polling_replaces_earlier_kinds() {
+        let builder = GlfwWindowBuilder::new(800, 600, "Test")
+            .event_polling(WindowEventKind::Key, true)
+            .all_polling(false)
+            .event_polling(WindowEventKind::Scroll, true);
+        assert_eq!(builder.all_polling, Some(false));
+        assert_eq!(builder.event_polling, vec![(WindowEventKind::Scroll, true)]);
+    }
+}
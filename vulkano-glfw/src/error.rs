@@ -0,0 +1,175 @@
+use std::cell::RefCell;
+use std::error;
+use std::fmt;
+
+use vk_sys as vk;
+
+use glfw;
+use glfw::{Callback, ErrorCallback};
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<GlfwError>> = RefCell::new(None);
+}
+
+/// An error reported by GLFW through its error callback
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GlfwError {
+    pub error: glfw::Error,
+    pub description: String,
+}
+
+impl error::Error for GlfwError {}
+
+impl fmt::Display for GlfwError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{} ({})", self.description, self.error)
+    }
+}
+
+/// The function to be used with the `RECORD_ERRORS` callback
+pub fn record_errors(error: glfw::Error, description: String, _: &()) {
+    LAST_ERROR.with(|last_error| {
+        *last_error.borrow_mut() = Some(GlfwError {
+            error: error,
+            description: description,
+        });
+    });
+}
+
+/// GLFW error callback which keeps the last error, so it can be attached to a `VulkanoGlfwError`.
+///
+/// `init` installs it. If GLFW is initialized with `glfw::init` instead, pass it there in place of
+/// `glfw::FAIL_ON_ERRORS`, otherwise the errors carry no GLFW message.
+pub static RECORD_ERRORS: Option<ErrorCallback<()>> =
+    Some(Callback { f: record_errors as fn(glfw::Error, String, &()), data: () });
+
+/// Forget the last recorded error before calling into GLFW
+pub fn clear_glfw_error() {
+    LAST_ERROR.with(|last_error| *last_error.borrow_mut() = None);
+}
+
+/// The error recorded since the last call to `clear_glfw_error`, if any
+pub fn take_glfw_error() -> Option<GlfwError> {
+    LAST_ERROR.with(|last_error| last_error.borrow_mut().take())
+}
+
+/// Error of the GLFW based window and surface functions.
+///
+/// GLFW reports the reason of a failure through its error callback. The message is
+/// included if GLFW was initialized with `init` or `RECORD_ERRORS`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VulkanoGlfwError {
+    /// The instance was not created with the extensions GLFW requires
+    ExtensionNotPresent(Option<GlfwError>),
+    /// GLFW could not find a Vulkan loader or the surface extension for the platform
+    InitializationFailed(Option<GlfwError>),
+    /// The window is already used by another surface or graphics API
+    NativeWindowInUse(Option<GlfwError>),
+    OutOfHostMemory(Option<GlfwError>),
+    OutOfDeviceMemory(Option<GlfwError>),
+    /// Any other `VkResult` returned while creating the surface
+    SurfaceCreationFailed { code: u32, glfw_error: Option<GlfwError> },
+    NoExtensions(Option<GlfwError>),
+    WindowCreationFailed(Option<GlfwError>),
+    MonitorNotFound,
+    /// GLFW was used on another thread than the main thread, see `is_main_thread`
+    WrongThread,
+}
+
+impl VulkanoGlfwError {
+    /// Map the result of `glfwCreateWindowSurface` to an error
+    pub fn from_surface_result(result: vk::Result, glfw_error: Option<GlfwError>) -> VulkanoGlfwError {
+        match result {
+            vk::ERROR_EXTENSION_NOT_PRESENT => VulkanoGlfwError::ExtensionNotPresent(glfw_error),
+            vk::ERROR_INITIALIZATION_FAILED => VulkanoGlfwError::InitializationFailed(glfw_error),
+            vk::ERROR_NATIVE_WINDOW_IN_USE_KHR => VulkanoGlfwError::NativeWindowInUse(glfw_error),
+            vk::ERROR_OUT_OF_HOST_MEMORY => VulkanoGlfwError::OutOfHostMemory(glfw_error),
+            vk::ERROR_OUT_OF_DEVICE_MEMORY => VulkanoGlfwError::OutOfDeviceMemory(glfw_error),
+            code => VulkanoGlfwError::SurfaceCreationFailed { code: code, glfw_error: glfw_error },
+        }
+    }
+
+    /// The message GLFW reported for the failure
+    pub fn glfw_error(&self) -> Option<&GlfwError> {
+        match *self {
+            VulkanoGlfwError::ExtensionNotPresent(ref glfw_error) |
+            VulkanoGlfwError::InitializationFailed(ref glfw_error) |
+            VulkanoGlfwError::NativeWindowInUse(ref glfw_error) |
+            VulkanoGlfwError::OutOfHostMemory(ref glfw_error) |
+            VulkanoGlfwError::OutOfDeviceMemory(ref glfw_error) |
+            VulkanoGlfwError::SurfaceCreationFailed { ref glfw_error, .. } |
+            VulkanoGlfwError::NoExtensions(ref glfw_error) |
+            VulkanoGlfwError::WindowCreationFailed(ref glfw_error) => glfw_error.as_ref(),
+            VulkanoGlfwError::MonitorNotFound |
+            VulkanoGlfwError::WrongThread => None,
+        }
+    }
+}
+
+impl error::Error for VulkanoGlfwError {
+    #[inline]
+    fn source(&self) -> Option<&(error::Error + 'static)> {
+        self.glfw_error().map(|glfw_error| glfw_error as &(error::Error + 'static))
+    }
+}
+
+impl fmt::Display for VulkanoGlfwError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        match *self {
+            VulkanoGlfwError::ExtensionNotPresent(_) => write!(fmt, "Required instance extension is not enabled"),
+            VulkanoGlfwError::InitializationFailed(_) => write!(fmt, "Vulkan is not available for GLFW"),
+            VulkanoGlfwError::NativeWindowInUse(_) => write!(fmt, "Window is already in use"),
+            VulkanoGlfwError::OutOfHostMemory(_) => write!(fmt, "Out of host memory"),
+            VulkanoGlfwError::OutOfDeviceMemory(_) => write!(fmt, "Out of device memory"),
+            VulkanoGlfwError::SurfaceCreationFailed { code, .. } =>
+                write!(fmt, "Could not create window surface (VkResult {})", code as i32),
+            VulkanoGlfwError::NoExtensions(_) => write!(fmt, "Could not load required extensions"),
+            VulkanoGlfwError::WindowCreationFailed(_) => write!(fmt, "Could not create GLFW window"),
+            VulkanoGlfwError::MonitorNotFound => write!(fmt, "Requested monitor is not connected"),
+            VulkanoGlfwError::WrongThread => write!(fmt, "GLFW used outside of the main thread"),
+        }?;
+        match self.glfw_error() {
+            Some(glfw_error) => write!(fmt, ": {}", glfw_error),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn glfw_error() -> Option<GlfwError> {
+        Some(GlfwError {
+            error: glfw::Error::ApiUnavailable,
+            description: "Vulkan: Loader not found".to_owned(),
+        })
+    }
+
+    #[test]
+    fn surface_results_map_to_variants() {
+        assert_eq!(VulkanoGlfwError::from_surface_result(vk::ERROR_EXTENSION_NOT_PRESENT, glfw_error()),
+                   VulkanoGlfwError::ExtensionNotPresent(glfw_error()));
+        assert_eq!(VulkanoGlfwError::from_surface_result(vk::ERROR_INITIALIZATION_FAILED, glfw_error()),
+                   VulkanoGlfwError::InitializationFailed(glfw_error()));
+        assert_eq!(VulkanoGlfwError::from_surface_result(vk::ERROR_NATIVE_WINDOW_IN_USE_KHR, glfw_error()),
+                   VulkanoGlfwError::NativeWindowInUse(glfw_error()));
+        assert_eq!(VulkanoGlfwError::from_surface_result(vk::ERROR_OUT_OF_HOST_MEMORY, None),
+                   VulkanoGlfwError::OutOfHostMemory(None));
+        assert_eq!(VulkanoGlfwError::from_surface_result(vk::ERROR_OUT_OF_DEVICE_MEMORY, None),
+                   VulkanoGlfwError::OutOfDeviceMemory(None));
+        assert_eq!(VulkanoGlfwError::from_surface_result(vk::ERROR_SURFACE_LOST_KHR, glfw_error()),
+                   VulkanoGlfwError::SurfaceCreationFailed { code: vk::ERROR_SURFACE_LOST_KHR, glfw_error: glfw_error() });
+    }
+
+    #[test]
+    fn display_includes_glfw_message() {
+        let err = VulkanoGlfwError::InitializationFailed(glfw_error());
+        assert_eq!(err.to_string(), format!("Vulkan is not available for GLFW: Vulkan: Loader not found ({})", glfw::Error::ApiUnavailable));
+        assert_eq!(VulkanoGlfwError::InitializationFailed(None).to_string(), "Vulkan is not available for GLFW");
+
+        let err = VulkanoGlfwError::SurfaceCreationFailed { code: vk::ERROR_SURFACE_LOST_KHR, glfw_error: None };
+        assert_eq!(err.to_string(), "Could not create window surface (VkResult -1000000000)");
+    }
+}
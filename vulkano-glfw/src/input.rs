@@ -0,0 +1,244 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+use glfw::{Action, Key, MouseButton, WindowEvent};
+
+use {GlfwWindow, GlfwWindowBuilder, VulkanoGlfwError, WindowEventKind};
+
+/// The events handled by `InputState::handle_event`
+const EVENT_KINDS: [WindowEventKind; 6] = [
+    WindowEventKind::Key,
+    WindowEventKind::MouseButton,
+    WindowEventKind::CursorPos,
+    WindowEventKind::CursorEnter,
+    WindowEventKind::Scroll,
+    WindowEventKind::Focus,
+];
+
+/// Keyboard and mouse state built from the events of a window.
+///
+/// Call `begin_frame` once per frame before handling the events of the frame. The
+/// just pressed and released keys and buttons, the cursor delta and the scroll offset
+/// then cover the events since the last frame. When the window loses the focus, all held
+/// keys and buttons count as released.
+#[derive(Clone, Debug, Default)]
+pub struct InputState {
+    keys_down: HashSet<Key>,
+    keys_pressed: HashSet<Key>,
+    keys_released: HashSet<Key>,
+    buttons_down: HashSet<MouseButton>,
+    buttons_pressed: HashSet<MouseButton>,
+    buttons_released: HashSet<MouseButton>,
+    cursor_position: Option<[f64; 2]>,
+    cursor_delta: [f64; 2],
+    scroll: [f64; 2],
+}
+
+impl InputState {
+    pub fn new() -> InputState {
+        InputState::default()
+    }
+
+    /// Enable the events the input state needs on the window
+    pub fn enable_polling(window: &mut GlfwWindow) -> Result<(), VulkanoGlfwError> {
+        for &kind in &EVENT_KINDS {
+            window.set_event_polling(kind, true)?;
+        }
+        Ok(())
+    }
+
+    /// Enable the events the input state needs on a window before it is built,
+    /// e.g. for the windows of a `WindowManager`
+    pub fn enable_builder_polling(builder: GlfwWindowBuilder) -> GlfwWindowBuilder {
+        EVENT_KINDS.iter().fold(builder, |builder, &kind| builder.event_polling(kind, true))
+    }
+
+    /// Forget the changes of the previous frame, the held keys and buttons are kept
+    pub fn begin_frame(&mut self) {
+        self.keys_pressed.clear();
+        self.keys_released.clear();
+        self.buttons_pressed.clear();
+        self.buttons_released.clear();
+        self.cursor_delta = [0.0, 0.0];
+        self.scroll = [0.0, 0.0];
+    }
+
+    /// Update the state with one event, events unrelated to input are ignored
+    pub fn handle_event(&mut self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::Key(key, _, action, _) => {
+                update_held(&mut self.keys_down, &mut self.keys_pressed, &mut self.keys_released, key, action);
+            },
+            WindowEvent::MouseButton(button, action, _) => {
+                update_held(&mut self.buttons_down, &mut self.buttons_pressed, &mut self.buttons_released, button, action);
+            },
+            WindowEvent::CursorPos(x, y) => {
+                if let Some(last) = self.cursor_position {
+                    self.cursor_delta[0] += x - last[0];
+                    self.cursor_delta[1] += y - last[1];
+                }
+                self.cursor_position = Some([x, y]);
+            },
+            WindowEvent::CursorEnter(_) => {
+                // the cursor jumps when it leaves and enters the window, which is not a movement
+                self.cursor_position = None;
+            },
+            WindowEvent::Scroll(x, y) => {
+                self.scroll[0] += x;
+                self.scroll[1] += y;
+            },
+            WindowEvent::Focus(false) => {
+                // the window gets no release events without focus, so nothing counts as held anymore
+                self.keys_released.extend(self.keys_down.drain());
+                self.buttons_released.extend(self.buttons_down.drain());
+            },
+            _ => (),
+        }
+    }
+
+    pub fn is_key_down(&self, key: Key) -> bool {
+        self.keys_down.contains(&key)
+    }
+
+    /// Whether the key was pressed since the frame began
+    pub fn is_key_pressed(&self, key: Key) -> bool {
+        self.keys_pressed.contains(&key)
+    }
+
+    /// Whether the key was released since the frame began
+    pub fn is_key_released(&self, key: Key) -> bool {
+        self.keys_released.contains(&key)
+    }
+
+    pub fn is_button_down(&self, button: MouseButton) -> bool {
+        self.buttons_down.contains(&button)
+    }
+
+    /// Whether the mouse button was pressed since the frame began
+    pub fn is_button_pressed(&self, button: MouseButton) -> bool {
+        self.buttons_pressed.contains(&button)
+    }
+
+    /// Whether the mouse button was released since the frame began
+    pub fn is_button_released(&self, button: MouseButton) -> bool {
+        self.buttons_released.contains(&button)
+    }
+
+    /// The cursor position in screen coordinates relative to the window, unknown until the cursor moved
+    pub fn cursor_position(&self) -> Option<[f64; 2]> {
+        self.cursor_position
+    }
+
+    /// How far the cursor moved since the frame began
+    pub fn cursor_delta(&self) -> [f64; 2] {
+        self.cursor_delta
+    }
+
+    /// The scroll offset accumulated since the frame began
+    pub fn scroll(&self) -> [f64; 2] {
+        self.scroll
+    }
+}
+
+/// Repeated presses of a held key do not count as new presses
+fn update_held<T>(down: &mut HashSet<T>, pressed: &mut HashSet<T>, released: &mut HashSet<T>, value: T, action: Action)
+    where T: Copy + Eq + Hash
+{
+    match action {
+        Action::Press => {
+            if down.insert(value) {
+                pressed.insert(value);
+            }
+        },
+        Action::Release => {
+            if down.remove(&value) {
+                released.insert(value);
+            }
+        },
+        Action::Repeat => (),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glfw::Modifiers;
+
+    fn key(key: Key, action: Action) -> WindowEvent {
+        WindowEvent::Key(key, 0, action, Modifiers::empty())
+    }
+
+    #[test]
+    fn key_press_and_release() {
+        let mut input = InputState::new();
+        input.handle_event(&key(Key::W, Action::Press));
+        input.handle_event(&key(Key::W, Action::Repeat));
+        assert!(input.is_key_down(Key::W));
+        assert!(input.is_key_pressed(Key::W));
+
+        input.begin_frame();
+        assert!(input.is_key_down(Key::W));
+        assert!(!input.is_key_pressed(Key::W));
+
+        input.handle_event(&key(Key::W, Action::Release));
+        assert!(!input.is_key_down(Key::W));
+        assert!(input.is_key_released(Key::W));
+    }
+
+    #[test]
+    fn press_and_release_in_one_frame() {
+        let mut input = InputState::new();
+        input.handle_event(&WindowEvent::MouseButton(MouseButton::Button1, Action::Press, Modifiers::empty()));
+        input.handle_event(&WindowEvent::MouseButton(MouseButton::Button1, Action::Release, Modifiers::empty()));
+        assert!(!input.is_button_down(MouseButton::Button1));
+        assert!(input.is_button_pressed(MouseButton::Button1));
+        assert!(input.is_button_released(MouseButton::Button1));
+    }
+
+    #[test]
+    fn cursor_delta_and_scroll_per_frame() {
+        let mut input = InputState::new();
+        input.handle_event(&WindowEvent::CursorPos(10.0, 20.0));
+        assert_eq!(input.cursor_delta(), [0.0, 0.0]);
+        input.handle_event(&WindowEvent::CursorPos(15.0, 18.0));
+        input.handle_event(&WindowEvent::CursorPos(17.0, 18.0));
+        input.handle_event(&WindowEvent::Scroll(0.0, 1.0));
+        input.handle_event(&WindowEvent::Scroll(0.0, 2.0));
+        assert_eq!(input.cursor_position(), Some([17.0, 18.0]));
+        assert_eq!(input.cursor_delta(), [7.0, -2.0]);
+        assert_eq!(input.scroll(), [0.0, 3.0]);
+
+        input.begin_frame();
+        assert_eq!(input.cursor_delta(), [0.0, 0.0]);
+        assert_eq!(input.scroll(), [0.0, 0.0]);
+        assert_eq!(input.cursor_position(), Some([17.0, 18.0]));
+    }
+
+    #[test]
+    fn focus_loss_releases_everything() {
+        let mut input = InputState::new();
+        input.handle_event(&key(Key::W, Action::Press));
+        input.handle_event(&WindowEvent::MouseButton(MouseButton::Button1, Action::Press, Modifiers::empty()));
+        input.begin_frame();
+
+        input.handle_event(&WindowEvent::Focus(false));
+        assert!(!input.is_key_down(Key::W));
+        assert!(input.is_key_released(Key::W));
+        assert!(!input.is_button_down(MouseButton::Button1));
+        assert!(input.is_button_released(MouseButton::Button1));
+
+        // a press after regaining the focus is a new press
+        input.handle_event(&WindowEvent::Focus(true));
+        input.handle_event(&key(Key::W, Action::Press));
+        assert!(input.is_key_pressed(Key::W));
+    }
+
+    #[test]
+    fn cursor_enter_is_no_movement() {
+        let mut input = InputState::new();
+        input.handle_event(&WindowEvent::CursorPos(10.0, 10.0));
+        input.handle_event(&WindowEvent::CursorEnter(true));
+        input.handle_event(&WindowEvent::CursorPos(300.0, 200.0));
+        assert_eq!(input.cursor_delta(), [0.0, 0.0]);
+    }
+}
@@ -1,12 +1,14 @@
 extern crate vulkano;
 extern crate vk_sys;
 extern crate glfw;
+#[macro_use]
+extern crate lazy_static;
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::ptr;
-use std::error;
-use std::fmt;
 use std::ffi::CString;
+use std::mem::ManuallyDrop;
 
 use std::sync::mpsc::Receiver;
 
@@ -15,78 +17,249 @@ use vulkano::instance::{Instance, InstanceExtensions, RawInstanceExtensions, Que
 
 use vulkano::swapchain::{Surface};
 
-use glfw::{Window, Context, Glfw, WindowMode, WindowEvent};
+use glfw::{Window, Context, Glfw, WindowMode, WindowEvent, FlushedMessages};
 
+mod builder;
+mod error;
+mod input;
+mod main_thread;
+mod manager;
+
+pub use builder::GlfwWindowBuilder;
+pub use error::{VulkanoGlfwError, GlfwError, record_errors, RECORD_ERRORS};
+pub use input::InputState;
+pub use main_thread::{init, is_main_thread};
+pub use manager::{WindowManager, WindowId, ManagerEvents};
+
+/// A GLFW window which can be used to create a Vulkan surface.
+///
+/// GLFW only allows window calls on the main thread, see `is_main_thread`. Calls into GLFW
+/// from any other thread are rejected with `VulkanoGlfwError::WrongThread`. The tracked surface
+/// state is read without GLFW, so it can be used from render threads.
+///
+/// The window can only be destroyed on the main thread. If it is dropped on another thread,
+/// e.g. together with the last reference to its surface, it stays open until the next call of
+/// `poll_events` on the main thread, on any window or the `WindowManager`.
 pub struct GlfwWindow {
-    window: Window,
+    window: ManuallyDrop<Window>,
+    events: Receiver<(f64, WindowEvent)>,
+    framebuffer_extent: Mutex<[u32; 2]>,
+    iconified: AtomicBool,
+    surface_out_of_date: AtomicBool,
+    /// The surface state events are always polled, these only decide if they are passed on
+    deliver_framebuffer_size: bool,
+    deliver_iconify: bool,
 }
 
-impl From<Window> for GlfwWindow {
-    fn from(window: Window) -> Self {
+impl From<(Window, Receiver<(f64, WindowEvent)>)> for GlfwWindow {
+    fn from((mut window, events): (Window, Receiver<(f64, WindowEvent)>)) -> Self {
+        // the window can only have been created on the main thread
+        main_thread::mark_main_thread();
+        // needed to keep track of the surface state
+        window.set_framebuffer_size_polling(true);
+        window.set_iconify_polling(true);
+        let (width, height) = window.get_framebuffer_size();
+        let iconified = window.is_iconified();
         GlfwWindow {
-            window: window,
+            window: ManuallyDrop::new(window),
+            events: events,
+            framebuffer_extent: Mutex::new([width as u32, height as u32]),
+            iconified: AtomicBool::new(iconified),
+            surface_out_of_date: AtomicBool::new(false),
+            deliver_framebuffer_size: true,
+            deliver_iconify: true,
         }
     }
 }
 
-pub fn create_glfw_window(glfw: Glfw, width: u32, height: u32, title: &str, mode: WindowMode) -> Option<(GlfwWindow, Receiver<(f64, WindowEvent)>)> {
-    match glfw.create_window(width, height, title, mode) {
-        Some((window, events)) => Some((GlfwWindow::from(window), events)),
-        None => None,
-    }
+pub fn create_glfw_window(glfw: Glfw, width: u32, height: u32, title: &str, mode: WindowMode) -> Option<GlfwWindow> {
+    glfw.create_window(width, height, title, mode).map(GlfwWindow::from)
+}
+
+/// The kinds of events a GLFW window can report
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum WindowEventKind {
+    Pos,
+    Size,
+    Close,
+    Refresh,
+    Focus,
+    Iconify,
+    FramebufferSize,
+    MouseButton,
+    CursorPos,
+    CursorEnter,
+    Scroll,
+    Key,
+    Char,
+    CharModifiers,
+    FileDrop,
 }
 
 impl GlfwWindow {
-    pub fn should_close(&self) -> bool {
-        self.window.should_close()
+    pub fn should_close(&self) -> Result<bool, VulkanoGlfwError> {
+        self.main_thread_window().map(|window| window.should_close())
     }
-}
 
-unsafe impl Send for GlfwWindow {}
-unsafe impl Sync for GlfwWindow {}
+    /// Enable or disable the delivery of one kind of event.
+    ///
+    /// Framebuffer size and iconify events are still received and tracked when they are
+    /// disabled, they are only no longer returned by `poll_events`.
+    pub fn set_event_polling(&mut self, kind: WindowEventKind, should_poll: bool) -> Result<(), VulkanoGlfwError> {
+        main_thread::check_main_thread()?;
+        match kind {
+            WindowEventKind::Pos => self.window.set_pos_polling(should_poll),
+            WindowEventKind::Size => self.window.set_size_polling(should_poll),
+            WindowEventKind::Close => self.window.set_close_polling(should_poll),
+            WindowEventKind::Refresh => self.window.set_refresh_polling(should_poll),
+            WindowEventKind::Focus => self.window.set_focus_polling(should_poll),
+            WindowEventKind::Iconify => self.deliver_iconify = should_poll,
+            WindowEventKind::FramebufferSize => self.deliver_framebuffer_size = should_poll,
+            WindowEventKind::MouseButton => self.window.set_mouse_button_polling(should_poll),
+            WindowEventKind::CursorPos => self.window.set_cursor_pos_polling(should_poll),
+            WindowEventKind::CursorEnter => self.window.set_cursor_enter_polling(should_poll),
+            WindowEventKind::Scroll => self.window.set_scroll_polling(should_poll),
+            WindowEventKind::Key => self.window.set_key_polling(should_poll),
+            WindowEventKind::Char => self.window.set_char_polling(should_poll),
+            WindowEventKind::CharModifiers => self.window.set_char_mods_polling(should_poll),
+            WindowEventKind::FileDrop => self.window.set_drag_and_drop_polling(should_poll),
+        }
+        Ok(())
+    }
 
-/// error while creating a GLFW-based surface
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
-pub enum VulkanoGlfwError {
-    /// General GLFW error
-    GlfwError{ code: u32 },
-    NoExtensions,
-}
+    /// Enable or disable the delivery of all kinds of events
+    pub fn set_all_polling(&mut self, should_poll: bool) -> Result<(), VulkanoGlfwError> {
+        main_thread::check_main_thread()?;
+        self.window.set_all_polling(should_poll);
+        // needed to keep track of the surface state
+        self.window.set_framebuffer_size_polling(true);
+        self.window.set_iconify_polling(true);
+        self.deliver_framebuffer_size = should_poll;
+        self.deliver_iconify = should_poll;
+        Ok(())
+    }
+
+    /// Iterate over all events received since the last call without blocking.
+    ///
+    /// Events are only delivered after `Glfw::poll_events` or `Glfw::wait_events` was called.
+    pub fn poll_events(&self) -> Result<WindowEvents, VulkanoGlfwError> {
+        main_thread::check_main_thread()?;
+        main_thread::destroy_dropped_windows();
+        Ok(self.pending_events())
+    }
+
+    /// The current size of the framebuffer in pixels, which is the extent a swapchain should use
+    pub fn framebuffer_extent(&self) -> [u32; 2] {
+        *self.framebuffer_extent.lock().unwrap()
+    }
+
+    /// Whether the window is currently minimised. Nothing should be presented while it is.
+    pub fn is_iconified(&self) -> bool {
+        self.iconified.load(Ordering::SeqCst)
+    }
+
+    /// Whether the framebuffer changed since the swapchain was created.
+    ///
+    /// The flag is updated while draining the events with `poll_events` and stays set
+    /// until it is reset with `set_surface_out_of_date(false)` after recreating the swapchain.
+    pub fn is_surface_out_of_date(&self) -> bool {
+        self.surface_out_of_date.load(Ordering::SeqCst)
+    }
 
-impl error::Error for VulkanoGlfwError {
-    #[inline]
-    fn description(&self) -> &str {
-        match *self {
-            VulkanoGlfwError::GlfwError{..} => "Genral Vulkan GLFW error",
-            VulkanoGlfwError::NoExtensions => "Could not load required extensions",
+    pub fn set_surface_out_of_date(&self, out_of_date: bool) {
+        self.surface_out_of_date.store(out_of_date, Ordering::SeqCst);
+    }
+
+    fn main_thread_window(&self) -> Result<&Window, VulkanoGlfwError> {
+        main_thread::check_main_thread().map(|_| &*self.window)
+    }
+
+    /// The thread must have been checked by the caller
+    fn pending_events(&self) -> WindowEvents {
+        WindowEvents {
+            window: self,
+            messages: glfw::flush_messages(&self.events),
+        }
+    }
+
+    fn track_event(&self, event: &WindowEvent) {
+        match *event {
+            WindowEvent::FramebufferSize(width, height) => {
+                *self.framebuffer_extent.lock().unwrap() = [width as u32, height as u32];
+                self.set_surface_out_of_date(true);
+            },
+            WindowEvent::Iconify(iconified) => {
+                self.iconified.store(iconified, Ordering::SeqCst);
+                if !iconified {
+                    self.set_surface_out_of_date(true);
+                }
+            },
+            _ => (),
         }
     }
 
-    #[inline]
-    fn cause(&self) -> Option<&error::Error> {
-        match *self {
-            _ => None,
+    /// Whether the caller asked for the event, the tracked ones may be disabled
+    fn is_delivered(&self, event: &WindowEvent) -> bool {
+        match *event {
+            WindowEvent::FramebufferSize(..) => self.deliver_framebuffer_size,
+            WindowEvent::Iconify(_) => self.deliver_iconify,
+            _ => true,
         }
     }
 }
 
-impl fmt::Display for VulkanoGlfwError {
-    #[inline]
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        write!(fmt, "{}", error::Error::description(self))
+/// Iterator over the pending events of a `GlfwWindow`, yielding the event time and the event
+pub struct WindowEvents<'a> {
+    window: &'a GlfwWindow,
+    messages: FlushedMessages<'a, (f64, WindowEvent)>,
+}
+
+impl<'a> Iterator for WindowEvents<'a> {
+    type Item = (f64, WindowEvent);
+
+    fn next(&mut self) -> Option<(f64, WindowEvent)> {
+        for (time, event) in &mut self.messages {
+            self.window.track_event(&event);
+            if self.window.is_delivered(&event) {
+                return Some((time, event));
+            }
+        }
+        None
     }
 }
 
+impl Drop for GlfwWindow {
+    fn drop(&mut self) {
+        if is_main_thread() {
+            unsafe {
+                ManuallyDrop::drop(&mut self.window);
+            }
+        }
+        else {
+            // destroying the window on another thread is not allowed, the main thread does it later
+            let window = unsafe { ptr::read(&*self.window) };
+            main_thread::defer_destroy(window);
+        }
+    }
+}
+
+// The GLFW window and the event receiver are only touched after checking that the current thread
+// is the one GLFW runs on, which is the same for all windows. Everything else is behind a mutex or atomic.
+unsafe impl Send for GlfwWindow {}
+unsafe impl Sync for GlfwWindow {}
+
 /// Create a surface from a GLFW window
 pub fn create_window_surface(instance: Arc<Instance>, window: GlfwWindow ) -> Result<Arc<Surface<GlfwWindow>>, VulkanoGlfwError> {
+    main_thread::check_main_thread()?;
     let internal_instance = instance.as_ref().internal_object();
     let internal_window = window.window.window_ptr();
     let mut internal_surface: vk_sys::SurfaceKHR = 0;
+    error::clear_glfw_error();
     let result = unsafe {
         glfw::ffi::glfwCreateWindowSurface(internal_instance, internal_window, ptr::null(), &mut internal_surface as *mut u64 )
     };
     if result != vk_sys::SUCCESS {
-        return Err(VulkanoGlfwError::GlfwError { code: result });
+        return Err(VulkanoGlfwError::from_surface_result(result, error::take_glfw_error()));
     }
     Ok(Arc::new(unsafe {
         Surface::from_raw_surface(instance, internal_surface, window)
@@ -102,9 +275,10 @@ pub fn get_required_instance_extensions(glfw: &Glfw) -> Result<InstanceExtension
 
 /// create RawInstanceExtensions from required GLFW extensions
 pub fn get_required_raw_instance_extensions(glfw: &Glfw) -> Result<RawInstanceExtensions, VulkanoGlfwError> {
+    error::clear_glfw_error();
     let exts = glfw.get_required_instance_extensions();
     if exts.is_none() {
-        return Err(VulkanoGlfwError::NoExtensions);
+        return Err(VulkanoGlfwError::NoExtensions(error::take_glfw_error()));
     }
 
     let iter = exts.unwrap().into_iter().map(|s| {
@@ -0,0 +1,86 @@
+use std::cell::Cell;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use glfw;
+use glfw::{Glfw, InitError, Window};
+
+use error::{VulkanoGlfwError, RECORD_ERRORS};
+
+static MAIN_THREAD_KNOWN: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    static IS_MAIN_THREAD: Cell<bool> = Cell::new(false);
+}
+
+/// A window dropped on another thread, it is only moved to the main thread to be destroyed there
+struct DroppedWindow(Window);
+
+unsafe impl Send for DroppedWindow {}
+
+lazy_static! {
+    static ref DROPPED_WINDOWS: Mutex<Vec<DroppedWindow>> = Mutex::new(Vec::new());
+}
+
+/// Initialize GLFW and make the current thread the main thread of all windows.
+///
+/// The errors reported by GLFW are recorded with `RECORD_ERRORS`.
+pub fn init() -> Result<Glfw, InitError> {
+    let glfw = glfw::init(RECORD_ERRORS)?;
+    mark_main_thread();
+    Ok(glfw)
+}
+
+/// Whether the current thread is the one GLFW is used on.
+///
+/// This is the thread `init` was called on. If GLFW was initialized with `glfw::init`,
+/// it is the thread the first window was created on.
+pub fn is_main_thread() -> bool {
+    // the thread local is gone while a thread shuts down, which is never the main thread
+    IS_MAIN_THREAD.try_with(|is_main| is_main.get()).unwrap_or(false)
+}
+
+/// Make the current thread the main thread, unless another thread already is
+pub fn mark_main_thread() {
+    if !MAIN_THREAD_KNOWN.swap(true, Ordering::SeqCst) {
+        IS_MAIN_THREAD.with(|is_main| is_main.set(true));
+    }
+}
+
+/// Keep a window dropped outside of the main thread until `destroy_dropped_windows` is called
+pub fn defer_destroy(window: Window) {
+    DROPPED_WINDOWS.lock().unwrap().push(DroppedWindow(window));
+}
+
+/// Destroy the windows dropped on other threads, must be called on the main thread
+pub fn destroy_dropped_windows() {
+    DROPPED_WINDOWS.lock().unwrap().clear();
+}
+
+pub fn check_main_thread() -> Result<(), VulkanoGlfwError> {
+    if is_main_thread() {
+        Ok(())
+    }
+    else {
+        Err(VulkanoGlfwError::WrongThread)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn only_the_first_thread_is_main_thread() {
+        mark_main_thread();
+        assert!(is_main_thread());
+
+        let other = thread::spawn(|| {
+            mark_main_thread();
+            check_main_thread()
+        });
+        assert_eq!(other.join().unwrap(), Err(VulkanoGlfwError::WrongThread));
+        assert!(is_main_thread());
+    }
+}
@@ -0,0 +1,186 @@
+use std::collections::BTreeMap;
+use std::collections::btree_map;
+use std::sync::Arc;
+
+use vulkano::instance::Instance;
+use vulkano::swapchain::Surface;
+
+use glfw::Glfw;
+
+use {GlfwWindow, GlfwWindowBuilder, VulkanoGlfwError, WindowEvents, create_window_surface};
+use main_thread;
+
+/// Identifies a window of a `WindowManager`. Ids are not reused after a window was removed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WindowId(usize);
+
+/// Owns the GLFW context and any number of windows, each with its own Vulkan surface.
+///
+/// Like the windows themselves, the manager has to be used on the main thread. Its methods
+/// check the thread before calling into GLFW, except for the direct access with `glfw_mut`.
+///
+/// A surface owns its window, so a window is only destroyed when the last reference to its
+/// surface is dropped. Swapchains keep a reference as well, they have to be dropped together
+/// with a removed window.
+pub struct WindowManager {
+    glfw: Glfw,
+    instance: Arc<Instance>,
+    windows: BTreeMap<WindowId, Arc<Surface<GlfwWindow>>>,
+    next_id: usize,
+}
+
+impl WindowManager {
+    /// The instance must have been created with the extensions of `get_required_instance_extensions`
+    pub fn new(glfw: Glfw, instance: Arc<Instance>) -> WindowManager {
+        // the manager is created where GLFW was initialized
+        main_thread::mark_main_thread();
+        WindowManager {
+            glfw: glfw,
+            instance: instance,
+            windows: BTreeMap::new(),
+            next_id: 0,
+        }
+    }
+
+    pub fn glfw(&self) -> &Glfw {
+        &self.glfw
+    }
+
+    /// Needed to build windows which are configured before they are added
+    pub fn glfw_mut(&mut self) -> &mut Glfw {
+        &mut self.glfw
+    }
+
+    pub fn instance(&self) -> &Arc<Instance> {
+        &self.instance
+    }
+
+    /// Build a window and create its surface, the event polling is configured by the builder
+    pub fn create_window(&mut self, builder: &GlfwWindowBuilder) -> Result<WindowId, VulkanoGlfwError> {
+        let window = builder.build(&mut self.glfw)?;
+        self.add_window(window)
+    }
+
+    /// Create the surface of a window built outside of the manager.
+    /// The event polling can only be changed before, see `GlfwWindowBuilder::event_polling`.
+    pub fn add_window(&mut self, window: GlfwWindow) -> Result<WindowId, VulkanoGlfwError> {
+        let surface = create_window_surface(self.instance.clone(), window)?;
+        let id = WindowId(self.next_id);
+        self.next_id += 1;
+        self.windows.insert(id, surface);
+        Ok(id)
+    }
+
+    /// Remove a window, it is closed as soon as its surface is not used anymore
+    pub fn remove_window(&mut self, id: WindowId) -> Option<Arc<Surface<GlfwWindow>>> {
+        self.windows.remove(&id)
+    }
+
+    pub fn window(&self, id: WindowId) -> Option<&GlfwWindow> {
+        self.windows.get(&id).map(|surface| surface.window())
+    }
+
+    pub fn surface(&self, id: WindowId) -> Option<&Arc<Surface<GlfwWindow>>> {
+        self.windows.get(&id)
+    }
+
+    /// The ids of all windows in the order they were added
+    pub fn window_ids(&self) -> Vec<WindowId> {
+        self.windows.keys().cloned().collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Process pending events without blocking and iterate over the events of each window
+    pub fn poll_events(&mut self) -> Result<ManagerEvents, VulkanoGlfwError> {
+        main_thread::check_main_thread()?;
+        main_thread::destroy_dropped_windows();
+        self.glfw.poll_events();
+        Ok(self.pending_events())
+    }
+
+    /// Wait until at least one event was received and iterate over the events of each window
+    pub fn wait_events(&mut self) -> Result<ManagerEvents, VulkanoGlfwError> {
+        main_thread::check_main_thread()?;
+        main_thread::destroy_dropped_windows();
+        self.glfw.wait_events();
+        Ok(self.pending_events())
+    }
+
+    /// Remove all windows the user asked to close and return their ids.
+    ///
+    /// Should be called after the events were processed, so the swapchains of the
+    /// returned windows can be dropped.
+    pub fn remove_closed_windows(&mut self) -> Result<Vec<WindowId>, VulkanoGlfwError> {
+        remove_closed(&mut self.windows, |surface| surface.window().should_close())
+    }
+
+    fn pending_events(&self) -> ManagerEvents {
+        ManagerEvents {
+            windows: self.windows.iter(),
+        }
+    }
+}
+
+/// Iterator over the windows of a `WindowManager` with their pending events, in the order the windows were added.
+///
+/// Events of a window which are not iterated stay pending until the next call of `poll_events` or `wait_events`.
+pub struct ManagerEvents<'a> {
+    windows: btree_map::Iter<'a, WindowId, Arc<Surface<GlfwWindow>>>,
+}
+
+impl<'a> Iterator for ManagerEvents<'a> {
+    type Item = (WindowId, WindowEvents<'a>);
+
+    fn next(&mut self) -> Option<(WindowId, WindowEvents<'a>)> {
+        // the thread was checked when the iterator was created
+        self.windows.next().map(|(&id, surface)| (id, surface.window().pending_events()))
+    }
+}
+
+/// Remove the windows which should close, nothing is removed if the check fails for any window
+fn remove_closed<W, F>(windows: &mut BTreeMap<WindowId, W>, should_close: F) -> Result<Vec<WindowId>, VulkanoGlfwError>
+    where F: Fn(&W) -> Result<bool, VulkanoGlfwError>
+{
+    let mut closed = Vec::new();
+    for (&id, window) in windows.iter() {
+        if should_close(window)? {
+            closed.push(id);
+        }
+    }
+    for id in &closed {
+        windows.remove(id);
+    }
+    Ok(closed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn windows(should_close: &[bool]) -> BTreeMap<WindowId, bool> {
+        should_close.iter().enumerate().map(|(id, &close)| (WindowId(id), close)).collect()
+    }
+
+    #[test]
+    fn remove_closed_keeps_open_windows() {
+        let mut windows = windows(&[false, true, false, true]);
+        let closed = remove_closed(&mut windows, |&close| Ok(close)).unwrap();
+        assert_eq!(closed, vec![WindowId(1), WindowId(3)]);
+        assert_eq!(windows.keys().cloned().collect::<Vec<_>>(), vec![WindowId(0), WindowId(2)]);
+    }
+
+    #[test]
+    fn remove_closed_removes_nothing_on_error() {
+        let mut windows = windows(&[true, false]);
+        let result = remove_closed(&mut windows, |&close| if close { Ok(true) } else { Err(VulkanoGlfwError::WrongThread) });
+        assert_eq!(result, Err(VulkanoGlfwError::WrongThread));
+        assert_eq!(windows.len(), 2);
+    }
+}